@@ -0,0 +1,69 @@
+// Generates src/advent/_all_days.rs, which declares a module for every
+// src/advent/dayNN.rs and maps day numbers to their solvers.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+const ADVENT_DIR: &str = "src/advent";
+const OUTPUT_FILE: &str = "src/advent/_all_days.rs";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", ADVENT_DIR);
+
+    let days = find_days(ADVENT_DIR);
+    let mut out = String::new();
+
+    out.push_str("// @generated by build.rs -- do not edit.\n\n");
+    for day in days.iter() {
+        out.push_str(&format!("mod day{:02};\n", day));
+    }
+
+    out.push_str("\n// Every day that has a solver, in order.\n");
+    out.push_str(&format!(
+        "pub const DAYS: [usize; {}] = [{}];\n",
+        days.len(),
+        days.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")));
+
+    out.push_str("\n// The solver for the given day, if there is one.\n");
+    out.push_str("fn solver(day: usize) -> Option<Box<dyn Runner>> {\n");
+    out.push_str("    match day {\n");
+    for day in days.iter() {
+        out.push_str(&format!(
+            "        {} => Some(Box::<day{:02}::Solver>::default()),\n",
+            day, day));
+    }
+    out.push_str("        _ => None,\n");
+    out.push_str("    }\n");
+    out.push_str("}\n");
+
+    write_if_changed(OUTPUT_FILE, &out);
+}
+
+// Day numbers of every dayNN.rs file in dir, sorted.
+fn find_days(dir: &str) -> Vec<usize> {
+    let mut days: Vec<usize> =
+        fs::read_dir(dir)
+           .expect("Couldn't read advent directory")
+           .filter_map(|entry| {
+               let name = entry.ok()?.file_name().into_string().ok()?;
+               name.strip_prefix("day")?
+                   .strip_suffix(".rs")?
+                   .parse::<usize>()
+                   .ok()
+           })
+           .collect();
+    days.sort_unstable();
+    days
+}
+
+// Avoid touching the output (and triggering rebuilds) when nothing changed.
+fn write_if_changed(path: &str, contents: &str) {
+    let path = Path::new(path);
+    if fs::read_to_string(path).map(|old| old == contents).unwrap_or(false) {
+        return;
+    }
+    fs::File::create(path)
+        .and_then(|mut f| f.write_all(contents.as_bytes()))
+        .expect("Couldn't write generated day list");
+}
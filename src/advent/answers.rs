@@ -0,0 +1,125 @@
+// Known answers for verifying solvers after refactoring.
+//
+// The answers file is TOML with a table per input file name, e.g.:
+//
+//   ["day01.txt"]
+//   part1 = 1711
+//   part2 = 1743
+//
+// Answers may be integers or strings. Grid answers are multi-line strings.
+
+use super::{Answer, Solution};
+use anyhow::{Error, format_err};
+use std::fs;
+use std::path::Path;
+
+pub const ANSWERS_PATH: &str = "answers.toml";
+
+pub struct KnownAnswers {
+    table: toml::Table,
+}
+
+// The result of checking one part against its known answer.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Check {
+    Correct,
+    Unknown,
+    Wrong { expected: String, actual: String },
+}
+
+impl KnownAnswers {
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let text = fs::read_to_string(path)
+                      .map_err(|e| format_err!("{}: {}", path, e))?;
+        let table = text.parse::<toml::Table>()
+                        .map_err(|e| format_err!("{}: {}", path, e))?;
+        Ok(KnownAnswers { table })
+    }
+
+    // Expected answer for a part of the puzzle with the given input file.
+    pub fn get(&self, input_path: &str, part: usize) -> Option<String> {
+        let file_name = Path::new(input_path).file_name()?.to_str()?;
+        let value = self.table.get(file_name)?
+                              .get(format!("part{}", part))?;
+        match value {
+            toml::Value::Integer(n) => Some(n.to_string()),
+            toml::Value::String(s) => Some(s.trim_matches('\n').to_string()),
+            _ => None,
+        }
+    }
+
+    // Check each part that was run against the known answers.
+    pub fn check(&self, input_path: &str, solution: &Solution)
+            -> Vec<(usize, Check)>
+    {
+        let parts = [(1, &solution.part1), (2, &solution.part2)];
+        parts.iter()
+             .filter_map(|(part, answer)| {
+                 let answer = answer.as_ref()?;
+                 Some((*part, self.check_part(input_path, *part, answer)))
+             })
+             .collect()
+    }
+
+    fn check_part(&self, input_path: &str, part: usize, answer: &Answer)
+            -> Check
+    {
+        let actual = match answer {
+            Answer::Grid(rows) => rows.join("\n"),
+            _ => answer.to_string(),
+        };
+        match self.get(input_path, part) {
+            None => Check::Unknown,
+            Some(expected) if expected == actual => Check::Correct,
+            Some(expected) => Check::Wrong { expected, actual },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Check, KnownAnswers};
+    use crate::advent::{Answer, Solution};
+
+    fn answers() -> KnownAnswers {
+        KnownAnswers {
+            table: r#"
+                ["day01.txt"]
+                part1 = 7
+                part2 = "abc"
+            "#.parse().unwrap()
+        }
+    }
+
+    #[test]
+    fn finds_answers_by_file_name() {
+        let answers = answers();
+        assert_eq!(Some("7".to_string()), answers.get("input/day01.txt", 1));
+        assert_eq!(Some("abc".to_string()), answers.get("day01.txt", 2));
+        assert_eq!(None, answers.get("input/day01_ex.txt", 1));
+    }
+
+    #[test]
+    fn checks_only_parts_that_ran() {
+        let solution = Solution {
+            part1: Some(Answer::Number(8)),
+            part2: None,
+            pictures: Vec::new(),
+        };
+        assert_eq!(vec![(1, Check::Wrong { expected: "7".to_string(),
+                                           actual: "8".to_string() })],
+                   answers().check("input/day01.txt", &solution));
+    }
+
+    #[test]
+    fn large_answers_do_not_wrap() {
+        let solution = Solution {
+            part1: Some(u64::MAX.into()),
+            part2: None,
+            pictures: Vec::new(),
+        };
+        assert_eq!(vec![(1, Check::Wrong { expected: "7".to_string(),
+                                           actual: u64::MAX.to_string() })],
+                   answers().check("input/day01.txt", &solution));
+    }
+}
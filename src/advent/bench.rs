@@ -0,0 +1,79 @@
+// Timing statistics for benchmark runs.
+
+use anyhow::{Error, format_err};
+use std::time::{Duration, Instant};
+
+// Samples of how long one phase of a solver (parsing, or a part) took.
+#[derive(Clone,Debug)]
+pub struct Timings {
+    pub phase: &'static str,
+    samples: Vec<Duration>,
+}
+
+impl Timings {
+    pub fn new(phase: &'static str) -> Self {
+        Timings {
+            phase,
+            samples: Vec::new(),
+        }
+    }
+
+    // Run f the given number of times, recording how long each run takes.
+    // Returns the result of the last run, or the first error.
+    pub fn measure<T, F>(&mut self, iterations: usize, mut f: F)
+            -> Result<T, Error>
+    where
+        F: FnMut() -> Result<T, Error>
+    {
+        let mut result = None;
+        for _ in 0..iterations {
+            let start = Instant::now();
+            let value = f()?;
+            self.samples.push(start.elapsed());
+            result = Some(value);
+        }
+        result.ok_or(format_err!("Need at least one iteration"))
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples.iter().min().cloned().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        match sorted.len() {
+            0 => Duration::default(),
+            n if n % 2 == 0 => (sorted[n/2 - 1] + sorted[n/2]) / 2,
+            n => sorted[n/2],
+        }
+    }
+
+    pub fn mean(&self) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::default();
+        }
+        self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timings;
+    use std::time::Duration;
+
+    #[test]
+    fn summarizes_samples() {
+        let mut timings = Timings::new("test");
+        timings.samples = [4, 1, 3, 8].iter()
+                                      .map(|&ms| Duration::from_millis(ms))
+                                      .collect();
+        assert_eq!(Duration::from_millis(1), timings.min());
+        assert_eq!(Duration::from_micros(3500), timings.median());
+        assert_eq!(Duration::from_millis(4), timings.mean());
+    }
+}
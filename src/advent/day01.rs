@@ -1,29 +1,32 @@
-use crate::advent::AdventSolver;
+use crate::advent::{AdventSolver, Answer};
 use crate::shared::io;
 use anyhow::Error;
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        let input = io::read_numbers_from_file::<u64>(input_path)?;
+    type Input = Vec<u64>;
 
-        // Part 1
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        io::read_numbers::<u64, _>(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer, Error> {
         let increased: usize =
             input.windows(2)
                  .map(|slice| if slice[1] > slice[0] { 1 } else { 0 })
                  .sum();
-        println!("Number of increasing depths: {}", increased);
+        Ok(increased.into())
+    }
 
-        // Part 2
+    fn part2(&self, input: &Self::Input) -> Result<Answer, Error> {
         let increased: usize =
             input.windows(4)
                  .map(|slice| if slice[1]+slice[2]+slice[3] >
                                  slice[0]+slice[1]+slice[2] { 1 } else { 0 })
                  .sum();
-        println!("Number of increasing depths: {}", increased);
-
-        Ok(())
+        Ok(increased.into())
     }
 }
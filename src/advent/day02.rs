@@ -1,14 +1,15 @@
-use crate::advent::AdventSolver;
-use crate::shared::io;
-use anyhow::{Error, format_err};
+use crate::advent::{AdventSolver, Answer};
+use crate::shared::io::{self, Line, ParseError};
+use anyhow::Error;
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
 
 #[derive(Copy,Clone)]
-enum Command {
+pub enum Command {
     Forward(i64),
     Down(i64),
     Up(i64),
@@ -16,15 +17,18 @@ enum Command {
 
 lazy_static! {
     static ref COMMAND_REGEX: Regex =
-        Regex::new(r"(?P<command>forward|down|up) (?P<amount>\d+)")
+        Regex::new(r"^(?P<command>forward|down|up) (?P<amount>\d+)$")
               .unwrap();
 }
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        let commands = Self::read_commands(input_path)?;
+    type Input = Vec<Command>;
 
-        // Part 1
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        Self::read_commands(input)
+    }
+
+    fn part1(&self, commands: &Self::Input) -> Result<Answer, Error> {
         let mut pos = 0;
         let mut depth = 0;
         for command in commands.iter() {
@@ -34,9 +38,10 @@ impl AdventSolver for Solver {
                 Command::Up(amount) => depth -= amount,
             }
         }
-        println!("P1: Pos {}, depth {} (product: {})", pos, depth, pos*depth);
+        Ok((pos*depth).into())
+    }
 
-        // Part 2
+    fn part2(&self, commands: &Self::Input) -> Result<Answer, Error> {
         let mut aim = 0;
         let mut pos = 0;
         let mut depth = 0;
@@ -50,24 +55,24 @@ impl AdventSolver for Solver {
                 Command::Up(amount) => aim -= amount,
             }
         }
-        println!("P2: Pos {}, depth {} (product: {})", pos, depth, pos*depth);
-
-        Ok(())
+        Ok((pos*depth).into())
     }
 }
 
 impl Solver {
-    fn read_commands(input_path: &str) -> Result<Vec<Command>, Error> {
-        io::read_lines_from_file(input_path)?
-           .iter()
-           .map(|line| Self::parse_command(line))
+    fn read_commands(input: &mut dyn BufRead) -> Result<Vec<Command>, Error> {
+        let lines = io::read_lines(input)?;
+        io::numbered(&lines)
+           .map(|line| Ok(Self::parse_command(line)?))
            .collect::<Result<Vec<Command>, Error>>()
     }
 
-    fn parse_command(text: &str) -> Result<Command, Error> {
-        match COMMAND_REGEX.captures(text) {
+    fn parse_command(line: Line) -> Result<Command, ParseError> {
+        match COMMAND_REGEX.captures(line.text) {
             Some(caps) => {
-                let amount = caps["amount"].parse::<i64>()?;
+                let amount = line.parse::<i64>(caps.name("amount")
+                                                   .unwrap()
+                                                   .as_str())?;
                 match &caps["command"] {
                     "forward" => Ok(Command::Forward(amount)),
                     "down" => Ok(Command::Down(amount)),
@@ -76,7 +81,7 @@ impl Solver {
                 }
             },
             None => {
-                Err(format_err!("Couldn't parse command: {}", text))
+                Err(line.error("expected forward, down or up and an amount"))
             }
         }
     }
@@ -1,6 +1,7 @@
-use crate::advent::AdventSolver;
+use crate::advent::{AdventSolver, Answer};
 use crate::shared::io;
 use anyhow::{Error, format_err};
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
@@ -9,23 +10,27 @@ pub struct Solver;
 const NUM_BITS: u64 = 12;
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        let diagnostic_report = Self::read_diagnostic_report(input_path)?;
+    type Input = Vec<usize>;
 
-        // Part 1
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        Self::read_diagnostic_report(input)
+    }
+
+    fn part1(&self, diagnostic_report: &Self::Input) -> Result<Answer, Error> {
         let mut gamma = 0;
         let mut epsilon = 0;
         for bit in 0..NUM_BITS {
-            if Self::most_common_value(&diagnostic_report, bit) == 1 {
+            if Self::most_common_value(diagnostic_report, bit) == 1 {
                 gamma += 1 << bit;
             } else {
                 epsilon += 1 << bit;
             }
         }
         let power_consumption = gamma * epsilon;
-        println!("Power consumption: {}", power_consumption);
+        Ok(power_consumption.into())
+    }
 
-        // Part 2
+    fn part2(&self, diagnostic_report: &Self::Input) -> Result<Answer, Error> {
         let mut oxygen_candidates = diagnostic_report.clone();
         let mut co2_candidates = diagnostic_report.clone();
         for bit in (0..NUM_BITS).rev() {
@@ -35,7 +40,7 @@ impl AdventSolver for Solver {
                 oxygen_candidates =
                     oxygen_candidates.iter()
                                      .filter(|v| *v & bitmask == common<<bit)
-                                     .map(|v| *v)
+                                     .copied()
                                      .collect();
             }
             let common = Self::least_common_value(&co2_candidates, bit);
@@ -43,7 +48,7 @@ impl AdventSolver for Solver {
                 co2_candidates =
                     co2_candidates.iter()
                                   .filter(|v| *v & bitmask == common<<bit)
-                                  .map(|v| *v)
+                                  .copied()
                                   .collect();
             }
         }
@@ -54,27 +59,33 @@ impl AdventSolver for Solver {
             Err(format_err!("Found {} co2 diagnostic candidates",
                             co2_candidates.len()))
         } else {
-            println!("Life support rating: {}",
-                     oxygen_candidates[0] * co2_candidates[0]);
-            Ok(())
+            Ok((oxygen_candidates[0] * co2_candidates[0]).into())
         }
     }
 }
 
 impl Solver {
-    fn read_diagnostic_report(input_path: &str) -> Result<Vec<usize>, Error> {
-        let lines = io::read_lines_from_file(input_path)?;
+    fn read_diagnostic_report(input: &mut dyn BufRead) -> Result<Vec<usize>, Error> {
+        let lines = io::read_lines(input)?;
         Self::read_diagnostic_lines(&lines)
     }
 
-    fn read_diagnostic_lines<T: AsRef<str>>(lines: &Vec<T>)
+    fn read_diagnostic_lines<T: AsRef<str>>(lines: &[T])
             -> Result<Vec<usize>, Error> {
-        Ok(lines.iter()
-                .map(|line| usize::from_str_radix(line.as_ref(), 2).unwrap())
-                .collect())
+        io::numbered(lines)
+            .map(|line| {
+                match line.text.find(|c| c != '0' && c != '1') {
+                    Some(i) => Err(line.error_at(i + 1,
+                                                 "expected a binary digit")
+                                   .into()),
+                    None => usize::from_str_radix(line.text, 2)
+                                  .map_err(|e| line.error(e).into()),
+                }
+            })
+            .collect()
     }
 
-    fn most_common_value(vec: &Vec<usize>, bit: u64) -> usize {
+    fn most_common_value(vec: &[usize], bit: u64) -> usize {
         let bitmask = 1 << bit;
         let sum: usize = vec.iter()
                             .filter(|v| *v & bitmask != 0)
@@ -82,7 +93,7 @@ impl Solver {
         (sum*2 >= vec.len()) as usize
     }
 
-    fn least_common_value(vec: &Vec<usize>, bit: u64) -> usize {
+    fn least_common_value(vec: &[usize], bit: u64) -> usize {
         if Self::most_common_value(vec, bit) == 0 { 1 } else { 0 }
     }
 }
@@ -1,9 +1,13 @@
-use crate::advent::AdventSolver;
-use crate::shared::io;
+use crate::advent::{AdventSolver, Answer};
+use crate::shared::io::{self, Line, ParseError};
 use anyhow::{Error, format_err};
+use std::io::BufRead;
 
 #[derive(Default)]
-pub struct Solver {
+pub struct Solver;
+
+#[derive(Default)]
+pub struct Game {
     number_sequence: Vec<u8>,
     boards: Vec<Board>,
 }
@@ -14,54 +18,77 @@ pub struct Board {
 }
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        self.read_input(input_path)?;
+    type Input = Game;
+
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        let mut game = Game::default();
+        game.read_input(input)?;
+        Ok(game)
+    }
+
+    fn part1(&self, game: &Self::Input) -> Result<Answer, Error> {
+        game.winning_scores()
+            .first()
+            .map(|&score| score.into())
+            .ok_or(format_err!("No board ever won"))
+    }
+
+    fn part2(&self, game: &Self::Input) -> Result<Answer, Error> {
+        game.winning_scores()
+            .last()
+            .map(|&score| score.into())
+            .ok_or(format_err!("No board ever won"))
+    }
+}
+
+impl Game {
+    // Play until every board has won, returning the scores in order of
+    // winning.
+    fn winning_scores(&self) -> Vec<u64> {
         let mut active_boards = self.boards.clone();
-        for (i, &number) in self.number_sequence.iter().enumerate() {
-            // Announce the number
-            println!("Round {}, drew {}!", i, number);
+        let mut winning_scores = Vec::new();
+        for &number in self.number_sequence.iter() {
             for board in active_boards.iter_mut() {
                 board.mark_if_present(number);
                 if board.won() {
-                    println!("Winning board: {}", board.score(number));
+                    winning_scores.push(board.score(number));
                 }
             }
             // Remove winners from the game
             active_boards = active_boards.iter()
                                          .filter(|board| !board.won())
-                                         .map(|board| board.clone())
+                                         .cloned()
                                          .collect();
-            if active_boards.len() == 0 {
-                println!("No active boards left, ending game.");
+            if active_boards.is_empty() {
                 break;
             }
         }
-        Ok(())
+        winning_scores
     }
-}
 
-impl Solver {
-    fn read_input(&mut self, input_path: &str) -> Result<(), Error> {
-        let lines = io::read_lines_from_file(input_path)?;
-        self.load_number_sequence(&lines[0])?;
+    fn read_input(&mut self, input: &mut dyn BufRead) -> Result<(), Error> {
+        let lines = io::read_lines(input)?;
+        let lines: Vec<Line> = io::numbered(&lines).collect();
+        let first = lines.first().ok_or(format_err!("Empty input"))?;
+        self.load_number_sequence(first)?;
         self.read_boards(&lines[1..])
     }
 
-    fn load_number_sequence(&mut self, sequence_str: &str) -> Result<(), Error>
+    fn load_number_sequence(&mut self, line: &Line) -> Result<(), Error>
     {
-        self.number_sequence =
-            sequence_str.split(",")
-                        .map(|s| Ok(s.parse::<u8>()?))
-                        .collect::<Result<Vec<u8>, Error>>()?;
+        self.number_sequence = line.parse_list(",")?;
         Ok(())
     }
 
-    fn read_boards(&mut self, lines: &[String]) -> Result<(), Error>
+    fn read_boards(&mut self, lines: &[Line]) -> Result<(), Error>
     {
-        if lines.len() == 0 {
+        if lines.is_empty() {
             Ok(())
-        } else if lines[0] != "" {
-            Err(format_err!("Expected blank line, got: {}", lines[0]))
+        } else if !lines[0].text.is_empty() {
+            Err(lines[0].error("expected a blank line before the board").into())
+        } else if lines.len() < 6 {
+            Err(lines[lines.len()-1].error("board has fewer than 5 rows")
+                                    .into())
         } else {
             self.boards.push(Board::from_text(&lines[1..=5])?);
             self.read_boards(&lines[6..])?;
@@ -71,12 +98,17 @@ impl Solver {
 }
 
 impl Board {
-    fn from_text(lines: &[String]) -> Result<Self, Error>
+    fn from_text(lines: &[Line]) -> Result<Self, ParseError>
     {
         let mut board = Board::default();
         for line in lines {
-            for v in line.split_whitespace() {
-                board.cells.push((v.parse::<u8>()?, false));
+            let row: Vec<&str> = line.text.split_whitespace().collect();
+            if row.len() != 5 {
+                return Err(line.error(format!("expected 5 numbers, found {}",
+                                              row.len())));
+            }
+            for v in row {
+                board.cells.push((line.parse::<u8>(v)?, false));
             }
         }
         Ok(board)
@@ -89,7 +121,7 @@ impl Board {
 
     fn mark_if_present(&mut self, number: u8)
     {
-        for mut cell in self.cells.iter_mut() {
+        for cell in self.cells.iter_mut() {
             if cell.0 == number {
                 cell.1 = true;
             }
@@ -100,7 +132,7 @@ impl Board {
     {
         let number = number as u64;
         self.cells.iter()
-                  .filter(|c| c.1 == false)
+                  .filter(|c| !c.1)
                   .map(|c| c.0 as u64)
                   .sum::<u64>() * number
     }
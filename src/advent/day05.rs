@@ -1,79 +1,85 @@
-use crate::advent::AdventSolver;
+use crate::advent::{AdventSolver, Answer};
 use crate::pos;
-use crate::shared::grid::{InfiniteGrid, Pos};
-use crate::shared::io;
-use anyhow::{Error, format_err};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::shared::grid::{InfiniteGrid, Pos, UnboundedGrid};
+use crate::shared::io::{self, ParseError};
+use anyhow::Error;
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
 
 pub struct Line {
-    x1: isize,
-    y1: isize,
-    x2: isize,
-    y2: isize,
+    start: Pos,
+    end: Pos,
 }
 
-lazy_static! {
-    static ref LINE_REGEX: Regex =
-        Regex::new(r"(?P<x1>\d+),(?P<y1>\d+) -> (?P<x2>\d+),(?P<y2>\d+)")
-              .unwrap();
+impl AdventSolver for Solver {
+    type Input = Vec<Line>;
+
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error>
+    {
+        Self::read_lines(input)
+    }
+
+    // Part 1: Horizontal and vertical lines only
+    fn part1(&self, lines: &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(Self::count_overlaps(lines, false).into())
+    }
+
+    // Part 2: Diagonal lines too
+    fn part2(&self, lines: &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(Self::count_overlaps(lines, true).into())
+    }
 }
 
-impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error>
+impl Solver {
+    // Count the points where at least two lines overlap.
+    fn count_overlaps(lines: &[Line], include_diagonals: bool) -> usize
     {
-        let lines = Self::read_lines(&input_path)?;
         let mut map: InfiniteGrid<u64> = InfiniteGrid::new(0);
         for line in lines.iter() {
-            let mut x = line.x1;
-            let mut y = line.y1;
-            let xstep = line.x2.cmp(&line.x1) as isize;
-            let ystep = line.y2.cmp(&line.y1) as isize;
-            // For part 1, uncomment
-            //if xstep != 0 && ystep != 0 {
-                //continue;
-            //}
+            let step = (line.end - line.start).signum();
+            if !include_diagonals && step.row != 0 && step.col != 0 {
+                continue;
+            }
+            let mut pos = line.start;
             loop {
-                map[pos!(x, y)] += 1;
-                if x == line.x2 && y == line.y2 {
+                map[pos] += 1;
+                if pos == line.end {
                     break;
                 }
-                x += xstep;
-                y += ystep;
+                pos = pos.step_toward(&line.end);
             }
         }
-        println!("Overlapping points: {}",
-                 map.crop()
-                    .iter()
-                    .map(|row| row.iter().filter(|&&v| v > 1).count())
-                    .sum::<usize>());
-        Ok(())
+        map.count_where(|&v| v > 1)
     }
-}
 
-impl Solver {
-    fn read_lines(input_path: &str) -> Result<Vec<Line>, Error>
+    fn read_lines(input: &mut dyn BufRead) -> Result<Vec<Line>, Error>
     {
-        let mut result = Vec::new();
-        let lines = io::read_lines_from_file(input_path)?;
-        for line in lines {
-            match LINE_REGEX.captures(&line) {
-                Some(caps) => {
-                    result.push(Line {
-                        x1: caps["x1"].parse::<isize>()?,
-                        y1: caps["y1"].parse::<isize>()?,
-                        x2: caps["x2"].parse::<isize>()?,
-                        y2: caps["y2"].parse::<isize>()?,
-                    });
-                },
-                None => {
-                    return Err(format_err!("Parse error: {}", line));
-                },
-            }
-        }
-        Ok(result)
+        let lines = io::read_lines(input)?;
+        io::numbered(&lines)
+            .map(|line| Ok(Self::parse_line(line)?))
+            .collect()
+    }
+
+    // Parse a line written "x1,y1 -> x2,y2".
+    fn parse_line(line: io::Line) -> Result<Line, ParseError>
+    {
+        let (start, end) =
+            line.text.split_once(" -> ")
+                     .ok_or_else(|| line.error("expected x1,y1 -> x2,y2"))?;
+        Ok(Line {
+            start: Self::parse_point(&line, start)?,
+            end: Self::parse_point(&line, end)?,
+        })
+    }
+
+    fn parse_point(line: &io::Line, text: &str) -> Result<Pos, ParseError>
+    {
+        let (x, y) = text.split_once(",")
+                         .ok_or_else(|| line.error_in(text, "expected x,y"))?;
+        Ok(pos!(line.parse::<isize>(y)?, line.parse::<isize>(x)?))
     }
 }
@@ -1,27 +1,51 @@
-use crate::advent::AdventSolver;
-use anyhow::Error;
-use std::fs;
+use crate::advent::{AdventSolver, Answer};
+use crate::shared::io;
+use anyhow::{Error, format_err};
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        let fish =
-            fs::read_to_string(input_path)?
-               .trim()
-               .split(",")
-               .map(|s| Ok(s.parse::<usize>()?))
-               .collect::<Result<Vec<usize>, Error>>()?;
+    type Input = Vec<usize>;
 
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        let lines = io::read_lines(input)?;
+        let line = io::numbered(&lines).next()
+                                       .ok_or(format_err!("Empty input"))?;
+        let mut fish = Vec::new();
+        for field in line.text.split(',') {
+            let timer: usize = line.parse(field)?;
+            if timer > 8 {
+                return Err(line.error_in(field, "fish timer must be from 0 \
+                                                 to 8").into());
+            }
+            fish.push(timer);
+        }
+        Ok(fish)
+    }
+
+    fn part1(&self, fish: &Self::Input) -> Result<Answer, Error> {
+        Ok(Self::count_after_days(fish, 80).into())
+    }
+
+    fn part2(&self, fish: &Self::Input) -> Result<Answer, Error> {
+        Ok(Self::count_after_days(fish, 256).into())
+    }
+}
+
+impl Solver {
+    // Timers must be from 0 to 8, as parse_input checks.
+    fn count_after_days(fish: &[usize], days: usize) -> usize
+    {
         // Initial count.
         let mut counts = vec![0; 9];
-        for fish in fish.iter() {
-            counts[*fish] += 1;
+        for &fish in fish.iter() {
+            counts[fish] += 1;
         }
 
         // Update lanternfish counts every day.
-        for day in 1..=256 {
+        for _ in 0..days {
             counts = [
               /* New 0 */ counts[1],
               /* New 1 */ counts[2],
@@ -33,13 +57,23 @@ impl AdventSolver for Solver {
               /* New 7 */ counts[8],
               /* New 8 */ counts[0],
             ].to_vec();
-
-            if day == 80 || day == 256 {
-                println!("After {} days, fish count is {}.",
-                         day, counts.iter().sum::<usize>());
-            }
         }
 
-        Ok(())
+        counts.iter().sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Solver;
+    use crate::advent::AdventSolver;
+    use crate::shared::io::ParseError;
+
+    #[test]
+    fn rejects_timer_above_8() {
+        let error = Solver.parse_input(&mut "3,4,9,1\n".as_bytes())
+                          .expect_err("Input should not parse");
+        let error = error.downcast::<ParseError>().unwrap();
+        assert_eq!((1, 5), (error.line, error.column));
     }
 }
@@ -1,6 +1,7 @@
-use crate::advent::AdventSolver;
-use anyhow::Error;
-use std::fs;
+use crate::advent::{AdventSolver, Answer};
+use crate::shared::io;
+use anyhow::{Error, format_err};
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
@@ -8,30 +9,32 @@ pub struct Solver;
 const EXAMPLE: [i64; 10] = [16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        let crabs =
-            fs::read_to_string(input_path)?
-               .trim()
-               .split(",")
-               .map(|s| Ok(s.parse::<i64>()?))
-               .collect::<Result<Vec<i64>, Error>>()?;
+    type Input = Vec<i64>;
 
-        // Part 1
-        let (position, fuel) =
-            Self::find_alignment(&crabs, |distance| distance);
-        println!("Position {} requires {} total fuel.", position, fuel);
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        let lines = io::read_lines(input)?;
+        let line = io::numbered(&lines).next()
+                                       .ok_or(format_err!("Empty input"))?;
+        Ok(line.parse_list(",")?)
+    }
 
-        // Part 2
-        let (position, fuel) =
-            Self::find_alignment(&crabs, |distance| distance*(distance+1)/2);
-        println!("Position {} requires {} total fuel.", position, fuel);
+    // Part 1: Fuel cost is linear in distance
+    fn part1(&self, crabs: &Self::Input) -> Result<Answer, Error> {
+        let (_position, fuel) =
+            Self::find_alignment(crabs, |distance| distance);
+        Ok(fuel.into())
+    }
 
-        Ok(())
+    // Part 2: Fuel cost is the triangular number of the distance
+    fn part2(&self, crabs: &Self::Input) -> Result<Answer, Error> {
+        let (_position, fuel) =
+            Self::find_alignment(crabs, |distance| distance*(distance+1)/2);
+        Ok(fuel.into())
     }
 }
 
 impl Solver {
-    fn find_alignment<F>(crabs: &Vec<i64>, fuel_used: F) -> (i64, i64)
+    fn find_alignment<F>(crabs: &[i64], fuel_used: F) -> (i64, i64)
     where
         F: Fn(i64) -> i64
     {
@@ -1,13 +1,21 @@
-use crate::advent::AdventSolver;
+use crate::advent::{AdventSolver, Answer};
 use crate::shared::io;
 use anyhow::{format_err, Error};
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
 
+// One line of input: the ten unique signal patterns, and the four output
+// digits.
+pub struct Entry {
+    patterns: String,
+    output: String,
+}
+
 // Non-mangled segment labels from problem description (we'll map to these when
 // we decipher data).
 //
@@ -43,54 +51,79 @@ lazy_static! {
 }
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error>
+    type Input = Vec<Entry>;
+
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error>
+    {
+        let mut entries = Vec::new();
+        let lines = io::read_lines(input)?;
+        for line in io::numbered(&lines) {
+            let (patterns, output) =
+                line.text.split_once(" | ")
+                         .ok_or_else(|| line.error("expected patterns | output"))?;
+            if let Some(i) = line.text.find(|c| !"abcdefg |".contains(c)) {
+                return Err(line.error_at(i + 1, "expected a segment a-g")
+                               .into());
+            }
+            entries.push(Entry {
+                patterns: patterns.to_string(),
+                output: output.to_string(),
+            });
+        }
+        Ok(entries)
+    }
+
+    // Part 1: Count of 1, 4, 7, and 8 digits in the outputs
+    fn part1(&self, entries: &Self::Input) -> Result<Answer, Error>
     {
-        // For part 1
         let mut count1478 = 0;
+        for entry in entries.iter() {
+            count1478 += Self::decode_output(entry)?
+                             .iter()
+                             .filter(|digit| [1, 4, 7, 8].contains(digit))
+                             .count();
+        }
+        Ok(count1478.into())
+    }
 
-        // For part 2
+    // Part 2: Sum of the decoded output values
+    fn part2(&self, entries: &Self::Input) -> Result<Answer, Error>
+    {
         let mut total = 0;
-
-        for line in io::read_lines_from_file(input_path)?.iter() {
-            let mut parts = line.split(" | ");
-            let patterns = parts.next()
-                                .ok_or(format_err!("Parse error: {}", line))?;
-            let output = parts.next()
-                              .ok_or(format_err!("Parse error: {}", line))?;
-
-            let map = Self::find_wire_mapping(patterns)
-                           .ok_or(format_err!("Failed to map: {}", patterns))?;
+        for entry in entries.iter() {
             let mut result = 0;
-            for output in output.split_whitespace() {
-                let digit = Self::unscramble_digit(output, &map).unwrap();
-                if [1, 4, 7, 8].contains(&digit) {
-                    count1478 += 1;
-                }
+            for digit in Self::decode_output(entry)? {
                 result *= 10;
                 result += digit;
             }
             total += result;
         }
-
-        println!("Count of 1, 4, 7, and 8 digits: {}", count1478);
-        println!("Total sum: {}", total);
-
-        Ok(())
+        Ok(total.into())
     }
 }
 
 impl Solver {
+    fn decode_output(entry: &Entry) -> Result<Vec<u64>, Error>
+    {
+        let map = Self::find_wire_mapping(&entry.patterns)
+                       .ok_or(format_err!("Failed to map: {}",
+                                          entry.patterns))?;
+        entry.output
+             .split_whitespace()
+             .map(|output| Self::unscramble_digit(output, &map)
+                                .ok_or(format_err!("Bad digit: {}", output)))
+             .collect()
+    }
+
     fn find_wire_mapping(patterns: &str) -> Option<HashMap<char, char>>
     {
         // Try every possible mapping of a-g -> a-g:
-        let segments = vec!['a', 'b', 'c', 'd', 'e', 'f', 'g'];
-        println!("Decoding: {}\n\n\n\n\n\n\n", patterns);
+        let segments = ['a', 'b', 'c', 'd', 'e', 'f', 'g'];
         'outer: for permutation in segments.iter().permutations(7) {
             let map = segments.iter()
                               .zip_eq(permutation)
                               .map(|(a, b)| (*a, *b))
                               .collect::<HashMap<char, char>>();
-            Self::render_patterns(patterns, &map);
             for pattern in patterns.split_whitespace() {
                 match Self::unscramble_digit(pattern, &map) {
                     Some(_digit) => {},
@@ -99,8 +132,6 @@ impl Solver {
             }
 
             // Found mapping
-            print!("\x1B[?25h"); // Show cursor
-            println!("");
             return Some(map);
         }
 
@@ -152,13 +183,13 @@ impl Solver {
                     _ => {},
                 }
             }
-            println!("");
+            println!();
         }
     }
 
     fn unscramble_digit(word: &str, map: &HashMap<char, char>) -> Option<u64>
     {
         let mapped = Self::map_pattern(word, map).iter().join("");
-        DIGIT_MAP.get(&*mapped).map(|n| *n)
+        DIGIT_MAP.get(&*mapped).copied()
     }
 }
@@ -1,12 +1,19 @@
-use crate::advent::AdventSolver;
+use crate::advent::{AdventSolver, Answer};
 use crate::shared::io;
-use anyhow::Error;
+use anyhow::{Error, format_err};
 use lazy_static::lazy_static;
 use std::collections::HashMap;
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
 
+// The score of a line, depending on whether it's corrupted or incomplete.
+enum Score {
+    Error(u64),
+    Completion(u64),
+}
+
 lazy_static! {
     static ref BRACKETS: HashMap<char, char> = [
         ('(', ')'),
@@ -31,36 +38,64 @@ lazy_static! {
 }
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        let program = io::read_lines_from_file(input_path)?;
+    type Input = Vec<String>;
+
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        let lines = io::read_lines(input)?;
+        for line in io::numbered(&lines) {
+            if let Some(i) = line.text.find(|c| !"()[]{}<>".contains(c)) {
+                return Err(line.error_at(i + 1, "expected a bracket").into());
+            }
+        }
+        Ok(lines)
+    }
+
+    fn part1(&self, program: &Self::Input) -> Result<Answer, Error> {
         let mut error_score = 0;
-        let mut completion_scores = Vec::new();
-        'next_line: for line in program.iter() {
-            let mut stack = Vec::new();
-            for c in line.chars() {
-                if BRACKETS.contains_key(&c) {
-                    stack.push(c);
-                } else {
-                    let open = stack.pop();
-                    if open.is_none() || BRACKETS[&open.unwrap()] != c {
-                        error_score += ERROR_SCORES[&c];
-                        continue 'next_line;
-                    }
-                }
+        for line in program.iter() {
+            if let Score::Error(score) = Self::score_line(line)? {
+                error_score += score;
             }
-            // Handle incomplete lines
-            let mut completion_score = 0;
-            while let Some(open) = stack.pop() {
-                completion_score *= 5;
-                completion_score += COMPLETION_SCORES[&open];
+        }
+        Ok(error_score.into())
+    }
+
+    fn part2(&self, program: &Self::Input) -> Result<Answer, Error> {
+        let mut completion_scores = Vec::new();
+        for line in program.iter() {
+            if let Score::Completion(score) = Self::score_line(line)? {
+                completion_scores.push(score);
             }
-            completion_scores.push(completion_score);
+        }
+        if completion_scores.is_empty() {
+            return Err(format_err!("No incomplete lines"));
         }
         completion_scores.sort();
+        Ok(completion_scores[completion_scores.len()/2].into())
+    }
+}
 
-        println!("Total error score: {}", error_score);
-        println!("Middle completion score: {}",
-                 completion_scores[completion_scores.len()/2]);
-        Ok(())
+impl Solver {
+    fn score_line(line: &str) -> Result<Score, Error> {
+        let mut stack = Vec::new();
+        for c in line.chars() {
+            if BRACKETS.contains_key(&c) {
+                stack.push(c);
+            } else {
+                let error_score = ERROR_SCORES.get(&c)
+                                              .ok_or(format_err!("Bad: {}", c))?;
+                let open = stack.pop();
+                if open.is_none() || BRACKETS[&open.unwrap()] != c {
+                    return Ok(Score::Error(*error_score));
+                }
+            }
+        }
+        // Handle incomplete lines
+        let mut completion_score = 0;
+        while let Some(open) = stack.pop() {
+            completion_score *= 5;
+            completion_score += COMPLETION_SCORES[&open];
+        }
+        Ok(Score::Completion(completion_score))
     }
 }
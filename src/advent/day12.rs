@@ -1,44 +1,114 @@
-use crate::advent::AdventSolver;
+use crate::advent::{AdventSolver, Answer};
 use crate::shared::io;
 use anyhow::{Error, format_err};
-use itertools::Itertools;
 use std::collections::HashMap;
+use std::io::BufRead;
 
 #[derive(Default)]
-pub struct Solver {
-    graph: HashMap<String, Vec<String>>,
-    in_part1: bool
+pub struct Solver;
+
+#[derive(Default)]
+pub struct Caves {
+    // Caves are interned as small integer IDs, indexing names and neighbors.
+    // Small caves are also numbered separately, to give each a bit in the
+    // visited set; large caves are never marked visited, so have no bit.
+    ids: HashMap<String, usize>,
+    names: Vec<String>,
+    small_bits: Vec<Option<usize>>,
+    small_count: usize,
+    neighbors: Vec<Vec<usize>>,
+}
+
+// Counts or lists paths between two interned caves. A path's state is its
+// last cave, the small caves visited so far as a bitmask, and whether a small
+// cave has been visited twice; counting remembers the count for each state.
+struct PathFinder<'a> {
+    caves: &'a Caves,
+    start: usize,
+    end: usize,
+    rule: Rule,
+    memo: HashMap<(usize, u64, bool), u64>,
+}
+
+// Which small caves a path may visit more than once.
+#[derive(Clone,Copy,Debug,PartialEq)]
+enum Rule {
+    // Part 1: Small caves at most once
+    SmallOnce,
+    // Part 2: A single small cave (other than start and end) twice
+    OneSmallTwice,
 }
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        self.read_graph(input_path)?;
-        self.in_part1 = true;
-        let paths = self.traverse_graph("start", "end", &Vec::new())?;
-        println!("Found {} paths through the graph.", paths.len());
-        self.in_part1 = false;
-        let paths = self.traverse_graph("start", "end", &Vec::new())?;
-        println!("Found {} paths through the graph.", paths.len());
-        Ok(())
+    type Input = Caves;
+
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        let mut caves = Caves::default();
+        caves.read_graph(input)?;
+        Ok(caves)
+    }
+
+    fn part1(&self, caves: &Self::Input) -> Result<Answer, Error> {
+        Ok(caves.count_paths("start", "end", Rule::SmallOnce)?.into())
+    }
+
+    fn part2(&self, caves: &Self::Input) -> Result<Answer, Error> {
+        Ok(caves.count_paths("start", "end", Rule::OneSmallTwice)?.into())
+    }
+
+    // Every path for part 1, one per line, for when the count isn't enough.
+    fn pictures(&self, caves: &Self::Input)
+            -> Result<Vec<(String, Answer)>, Error>
+    {
+        let paths = caves.traverse_graph("start", "end", Rule::SmallOnce)?;
+        Ok(vec![("Part 1 paths".to_string(),
+                 Answer::Grid(paths.iter().map(|p| p.join(",")).collect()))])
     }
 }
 
-impl Solver {
+impl Caves {
     fn add_edge(&mut self, left: &str, right: &str) {
-        self.graph.entry(left.to_string())
-                  .or_insert_with(|| Vec::new())
-                  .push(right.to_string());
-        self.graph.entry(right.to_string())
-                  .or_insert_with(|| Vec::new())
-                  .push(left.to_string());
+        let left = self.intern(left);
+        let right = self.intern(right);
+        self.neighbors[left].push(right);
+        self.neighbors[right].push(left);
     }
 
-    fn read_graph(&mut self, input_path: &str) -> Result<(), Error> {
-        let lines = io::read_lines_from_file(input_path)?;
-        for line in lines {
+    // The ID of cave, assigning the next one if it's new.
+    fn intern(&mut self, cave: &str) -> usize {
+        if let Some(&id) = self.ids.get(cave) {
+            return id;
+        }
+        let id = self.small_bits.len();
+        self.ids.insert(cave.to_string(), id);
+        self.names.push(cave.to_string());
+        if Self::is_small_cave(cave) {
+            self.small_bits.push(Some(self.small_count));
+            self.small_count += 1;
+        } else {
+            self.small_bits.push(None);
+        }
+        self.neighbors.push(Vec::new());
+        id
+    }
+
+    fn read_graph(&mut self, input: &mut dyn BufRead) -> Result<(), Error> {
+        let lines = io::read_lines(input)?;
+        for line in io::numbered(&lines) {
             let (left, right) =
-                line.split("-").next_tuple()
-                    .ok_or(format_err!("Parse error: {}", line))?;
+                line.text.split_once("-")
+                         .ok_or_else(|| line.error("expected cave-cave"))?;
+            for cave in [left, right] {
+                if cave.is_empty() || !cave.chars().all(char::is_alphabetic) {
+                    return Err(line.error_in(cave, "expected a cave name")
+                                   .into());
+                }
+            }
+            if !Self::is_small_cave(left) && !Self::is_small_cave(right) {
+                // Paths could go back and forth between them forever
+                return Err(line.error("two large caves can't be connected")
+                               .into());
+            }
             self.add_edge(left, right);
         }
         Ok(())
@@ -49,62 +119,173 @@ impl Solver {
         cave.chars().nth(0).unwrap().is_lowercase()
     }
 
-    fn can_visit(&self, cave: &str, prefix: &Vec<String>) -> bool {
-        let mut cave_counts: HashMap<&str, usize> = HashMap::new();
-        cave_counts.insert(cave, 0); // To avoid clunky checks later
-        for cave in prefix.iter() {
-            *cave_counts.entry(cave).or_insert(0) += 1;
+    // The number of paths from start to end allowed by rule, counted without
+    // building them. Paths that reach the same cave having visited the same
+    // small caves (and used up the same second visit) continue identically,
+    // so each such state is counted once and remembered.
+    fn count_paths(&self, start: &str, end: &str, rule: Rule)
+            -> Result<u64, Error>
+    {
+        let mut finder = self.path_finder(start, end, rule)?;
+        let visited = finder.bit(finder.start).unwrap_or(0);
+        Ok(finder.count_from(finder.start, visited, false))
+    }
+
+    // Every path from start to end allowed by rule, as cave names.
+    fn traverse_graph(&self, start: &str, end: &str, rule: Rule)
+            -> Result<Vec<Vec<String>>, Error>
+    {
+        let finder = self.path_finder(start, end, rule)?;
+        let visited = finder.bit(finder.start).unwrap_or(0);
+        let mut path = vec![finder.start];
+        let mut paths = Vec::new();
+        finder.list_from(visited, false, &mut path, &mut paths);
+        Ok(paths.iter()
+                .map(|path| path.iter()
+                                .map(|&cave| self.names[cave].clone())
+                                .collect())
+                .collect())
+    }
+
+    fn path_finder(&self, start: &str, end: &str, rule: Rule)
+            -> Result<PathFinder<'_>, Error>
+    {
+        let id = |cave: &str| {
+            self.ids.get(cave)
+                    .cloned()
+                    .ok_or(format_err!("No such cave: {}", cave))
+        };
+        let (start, end) = (id(start)?, id(end)?);
+        if self.small_count > 64 {
+            return Err(format_err!("Too many small caves to find paths: {}",
+                                   self.small_count));
         }
-        let small_count =
-            cave_counts.iter()
-                       .filter(|(k, _v)| Self::is_small_cave(k))
-                       .map(|(_k, v)| *v)
-                       .max().unwrap();
-
-        if self.in_part1 {
-            // Rule for part 1
-            if cave.chars().nth(0).unwrap().is_lowercase() {
-                cave_counts[cave] < 1
-            } else {
-                true
-            }
-        } else {
-            // Rule for part 2
-            if cave == "start" || cave == "end" {
-                cave_counts[cave] < 1
-            } else if Self::is_small_cave(cave) {
-                small_count < 2 || cave_counts[cave] < 1
-            } else {
-                true
+        Ok(PathFinder {
+            caves: self,
+            start,
+            end,
+            rule,
+            memo: HashMap::new(),
+        })
+    }
+}
+
+impl<'a> PathFinder<'a> {
+    // The visited-set bit of a small cave, or None for a large one.
+    fn bit(&self, cave: usize) -> Option<u64> {
+        self.caves.small_bits[cave].map(|b| 1 << b)
+    }
+
+    // The states reachable in one step from cave, as (next cave, visited,
+    // used_double).
+    fn moves(&self, cave: usize, visited: u64, used_double: bool)
+            -> Vec<(usize, u64, bool)>
+    {
+        let mut moves = Vec::new();
+        for &n in self.caves.neighbors[cave].iter() {
+            match self.bit(n) {
+                None => moves.push((n, visited, used_double)),
+                Some(bit) if visited & bit == 0 => {
+                    moves.push((n, visited | bit, used_double));
+                },
+                Some(_bit) if self.rule == Rule::OneSmallTwice &&
+                              !used_double &&
+                              n != self.start && n != self.end => {
+                    moves.push((n, visited, true));
+                },
+                Some(_bit) => {},
             }
         }
+        moves
     }
 
-    fn traverse_graph(&self, start: &str, end: &str, prefix: &Vec<String>)
-            -> Result<Vec<Vec<String>>, Error>
+    fn count_from(&mut self, cave: usize, visited: u64, used_double: bool)
+            -> u64
     {
+        if cave == self.end {
+            return 1;
+        }
+        if let Some(&count) = self.memo.get(&(cave, visited, used_double)) {
+            return count;
+        }
+        let count = self.moves(cave, visited, used_double)
+                        .into_iter()
+                        .map(|(n, visited, used_double)| {
+                            self.count_from(n, visited, used_double)
+                        })
+                        .sum();
+        self.memo.insert((cave, visited, used_double), count);
+        count
+    }
 
-        // Create a new prefix that includes our local start
-        let prefix = prefix.iter()
-                           .chain(&[start.to_string()])
-                           .cloned().collect();
-
-        // Base case, we're already at end
-        if !self.graph.contains_key(&start.to_string()) {
-            return Err(format_err!("No such cave: {}", start));
+    // Add to paths every complete path that continues path, which ends in
+    // the state given by its last cave, visited and used_double.
+    fn list_from(&self, visited: u64, used_double: bool,
+                 path: &mut Vec<usize>, paths: &mut Vec<Vec<usize>>)
+    {
+        let cave = path[path.len()-1];
+        if cave == self.end {
+            paths.push(path.clone());
+            return;
         }
-        if start == end {
-            return Ok(vec![prefix]);
+        for (n, visited, doubled) in self.moves(cave, visited, used_double) {
+            path.push(n);
+            self.list_from(visited, doubled, path, paths);
+            path.pop();
         }
+    }
+}
 
-        // Recursive case, traverse all neighbors
-        let mut paths = Vec::new();
-        for n in self.graph[&start.to_string()].iter() {
-            if self.can_visit(&n, &prefix) {
-                paths.append(&mut self.traverse_graph(n, end, &prefix)?);
-            }
+#[cfg(test)]
+mod test {
+    use super::{Caves, Rule};
+    use crate::shared::io::ParseError;
+
+    #[test]
+    fn counting_matches_enumeration() {
+        let mut caves = Caves::default();
+        caves.read_graph(&mut "dc-end\nHN-start\nstart-kj\ndc-start\n\
+                                dc-HN\nLN-dc\nHN-end\nkj-sa\nkj-HN\n\
+                                kj-dc\n".as_bytes())
+             .unwrap();
+        for (rule, expected) in [(Rule::SmallOnce, 19),
+                                 (Rule::OneSmallTwice, 103)] {
+            let paths = caves.traverse_graph("start", "end", rule).unwrap();
+            assert_eq!(expected, paths.len());
+            assert_eq!(expected as u64,
+                       caves.count_paths("start", "end", rule).unwrap());
         }
+        assert!(caves.count_paths("start", "nowhere", Rule::SmallOnce)
+                     .is_err());
+    }
 
-        Ok(paths)
+    #[test]
+    fn rejects_connected_large_caves() {
+        let mut caves = Caves::default();
+        let error = caves.read_graph(&mut "start-A\nA-B\nB-end\n".as_bytes())
+                         .expect_err("Input should not parse");
+        let error = error.downcast::<ParseError>().unwrap();
+        assert_eq!((2, 1), (error.line, error.column));
+    }
+
+    #[test]
+    fn only_small_caves_count_toward_limit() {
+        // 70 caves, but only start, end and c10..c69 are small
+        let mut caves = Caves::default();
+        for i in 0..70 {
+            let cave = if i < 10 {
+                format!("B{}", i)
+            } else {
+                format!("c{}", i)
+            };
+            caves.add_edge("start", &cave);
+            caves.add_edge(&cave, "end");
+        }
+        assert_eq!(70, caves.count_paths("start", "end", Rule::SmallOnce)
+                            .unwrap());
+        caves.add_edge("end", "c70");
+        caves.add_edge("end", "c71");
+        caves.add_edge("end", "c72");
+        assert!(caves.count_paths("start", "end", Rule::SmallOnce).is_err());
     }
 }
@@ -1,76 +1,110 @@
-use crate::advent::AdventSolver;
-use crate::shared::io;
+use crate::advent::{AdventSolver, Answer};
+use crate::pos;
+use crate::shared::grid::{Pos, Renderable, SparseGrid};
+use crate::shared::io::{self, Line, ParseError};
+use crate::shared::ocr;
 use anyhow::{Error, format_err};
 use std::collections::HashSet;
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
 
+// Fold along the vertical line x=n, or the horizontal line y=n.
 #[derive(Copy,Clone,Debug)]
-enum Fold {
-    X(u64),
-    Y(u64),
-}
-
-#[derive(Copy,Clone,Debug,Eq,Hash,PartialEq)]
-struct Pos {
-    x: u64,
-    y: u64,
+pub enum Fold {
+    X(isize),
+    Y(isize),
 }
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        let (mut points, folds) = Self::read_code_from_manual(input_path)?;
-        println!("Read {} points and {} fold instructions.",
-                 points.len(), folds.len());
+    type Input = (HashSet<Pos>, Vec<Fold>);
+
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        Self::read_code_from_manual(input)
+    }
 
+    // Part 1: Number of points after the first fold
+    fn part1(&self, (points, folds): &Self::Input) -> Result<Answer, Error> {
+        let mut points = points.clone();
+        let fold = folds.first().ok_or(format_err!("No fold instructions"))?;
+        Self::fold_points(&mut points, fold);
+        Ok(points.len().into())
+    }
+
+    // Part 2: The code revealed after all folds
+    fn part2(&self, (points, folds): &Self::Input) -> Result<Answer, Error> {
+        let mut points = points.clone();
         for fold in folds.iter() {
             Self::fold_points(&mut points, fold);
-            println!("After fold {:?}, number of points: {}",
-                     fold, points.len());
         }
+        Ok(ocr::recognize_points(&points)?.into())
+    }
 
-        println!("");
-        Self::render_points(&points);
-        Ok(())
+    fn pictures(&self, (points, folds): &Self::Input)
+            -> Result<Vec<(String, Answer)>, Error>
+    {
+        let mut points = points.clone();
+        for fold in folds.iter() {
+            Self::fold_points(&mut points, fold);
+        }
+        Ok(vec![("After all folds".to_string(),
+                 Self::render_points(&points))])
     }
 }
 
 impl Solver {
-    fn read_code_from_manual(input_path: &str)
+    fn read_code_from_manual(input: &mut dyn BufRead)
             -> Result<(HashSet<Pos>, Vec<Fold>), Error>
     {
-        let lines = io::read_lines_from_file(input_path)?;
-        let mut iter = lines.iter();
+        let lines = io::read_lines(input)?;
+        let mut iter = io::numbered(&lines);
         let mut points = HashSet::new();
         let mut folds = Vec::new();
 
-        // Read points
+        // Read points, up to the blank line
         for line in &mut iter {
-            if line.len() == 0 {
+            if line.text.is_empty() {
                 break;
             }
-            let mut parts = line.split(",");
-            let x = parts.next().unwrap().parse::<u64>()?;
-            let y = parts.next().unwrap().parse::<u64>()?;
-            points.insert(Pos { x: x, y: y });
+            if line.text.starts_with("fold") {
+                return Err(line.error("expected a blank line between the \
+                                       points and the folds").into());
+            }
+            points.insert(Self::parse_point(line)?);
         }
 
         // Read folds
         for line in &mut iter {
-            let mut parts = line.split("=");
-            let axis = parts.next().unwrap().chars().last().unwrap();
-            let value = parts.next().unwrap().parse::<u64>()?;
-            folds.push(match axis {
-                'x' => Fold::X(value),
-                'y' => Fold::Y(value),
-                _ => return Err(format_err!("Bad axis: {}", axis)),
-            });
+            folds.push(Self::parse_fold(line)?);
         }
 
         Ok((points, folds))
     }
 
+    // Parse a point written "x,y".
+    fn parse_point(line: Line) -> Result<Pos, ParseError> {
+        let (x, y) = line.text
+                         .split_once(",")
+                         .ok_or_else(|| line.error("expected a point x,y"))?;
+        Ok(pos!(line.parse::<isize>(y)?, line.parse::<isize>(x)?))
+    }
+
+    // Parse a fold written "fold along x=n" or "fold along y=n".
+    fn parse_fold(line: Line) -> Result<Fold, ParseError> {
+        let (axis, value) =
+            line.text.strip_prefix("fold along ")
+                     .and_then(|rest| rest.split_once("="))
+                     .ok_or_else(|| line.error("expected fold along x=n or \
+                                                fold along y=n"))?;
+        let value = line.parse::<isize>(value)?;
+        match axis {
+            "x" => Ok(Fold::X(value)),
+            "y" => Ok(Fold::Y(value)),
+            _ => Err(line.error_in(axis, "expected axis x or y")),
+        }
+    }
+
     fn fold_points(points: &mut HashSet<Pos>, fold: &Fold) {
         // Points that will be re-added (after folding) to the set
         let mut new_points = Vec::new();
@@ -80,16 +114,16 @@ impl Solver {
         points.retain(|point| {
             match fold {
                 Fold::X(value) => {
-                    if point.x > *value {
-                        new_points.push(Pos {x: 2*value-point.x, y: point.y});
+                    if point.col > *value {
+                        new_points.push(point.reflect_across_col(*value));
                         false
                     } else {
                         true
                     }
                 },
                 Fold::Y(value) => {
-                    if point.y > *value {
-                        new_points.push(Pos {x: point.x, y: 2*value-point.y});
+                    if point.row > *value {
+                        new_points.push(point.reflect_across_row(*value));
                         false
                     } else {
                         true
@@ -102,20 +136,68 @@ impl Solver {
         }
     }
 
-    // Draw the state of the given points in the console.
-    fn render_points(points: &HashSet<Pos>) {
-        let max_x = points.iter().map(|p| p.x).max().unwrap();
-        let max_y = points.iter().map(|p| p.y).max().unwrap();
-        for y in 0..=max_y {
-            for x in 0..=max_x {
-                if points.contains(&Pos { x: x, y: y}) {
-                    print!("█");
-                } else {
-                    print!(" ");
-                }
-            }
-            println!("");
+    // Draw the state of the given points as a picture.
+    fn render_points(points: &HashSet<Pos>) -> Answer {
+        let mut grid = SparseGrid::new(false);
+        for &point in points.iter() {
+            grid[point] = true;
         }
-        println!("");
+        let lines = grid.render_with(|&dot| if dot { '█' } else { ' ' })
+                        .lines();
+        Answer::Grid(lines)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Solver;
+    use crate::shared::io::ParseError;
+
+    // The (line, column, message) of the error from parsing text.
+    fn parse_error(text: &str) -> (usize, usize, String) {
+        let error = Solver::read_code_from_manual(&mut text.as_bytes())
+                           .expect_err("Input should not parse");
+        let error = error.downcast::<ParseError>().unwrap();
+        (error.line, error.column, error.message)
+    }
+
+    #[test]
+    fn parses_points_and_folds() {
+        let (points, folds) = Solver::read_code_from_manual(
+            &mut "6,10\n0,14\n\nfold along y=7\nfold along x=5\n".as_bytes())
+            .unwrap();
+        assert_eq!(2, points.len());
+        assert_eq!(2, folds.len());
+    }
+
+    #[test]
+    fn reports_bad_coordinates() {
+        assert_eq!((2, 1, "expected a point x,y".to_string()),
+                   parse_error("6,10\n0;14\n\nfold along y=7\n"));
+        let (line, column, message) =
+            parse_error("6,10\n0,-\n\nfold along y=7\n");
+        assert_eq!((2, 3), (line, column));
+        assert!(message.contains("\"-\""), "{}", message);
+    }
+
+    #[test]
+    fn reports_bad_folds() {
+        let (line, column, message) =
+            parse_error("6,10\n\nfold along y=7\nfold over x=5\n");
+        assert_eq!((4, 1), (line, column));
+        assert!(message.starts_with("expected fold along"), "{}", message);
+        assert_eq!((3, 12, "expected axis x or y".to_string()),
+                   parse_error("6,10\n\nfold along z=7\n"));
+        let (line, column, message) =
+            parse_error("6,10\n\nfold along x=five\n");
+        assert_eq!((3, 14), (line, column));
+        assert!(message.contains("\"five\""), "{}", message);
+    }
+
+    #[test]
+    fn reports_missing_separator() {
+        let (line, column, message) = parse_error("6,10\nfold along y=7\n");
+        assert_eq!((2, 1), (line, column));
+        assert!(message.starts_with("expected a blank line"), "{}", message);
     }
 }
@@ -1,42 +1,54 @@
-use crate::advent::AdventSolver;
-use crate::shared::io;
+use crate::advent::{AdventSolver, Answer};
+use crate::shared::io::{self, Line, ParseError};
 use anyhow::{Error, format_err};
 use itertools::Itertools;
 use std::collections::HashMap;
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
 
 #[derive(Debug)]
-struct Rule {
+pub struct Rule {
     c1: char,
     c2: char,
     i: char,
 }
 
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        let (polymer, rules) = Self::read_input(input_path)?;
-        let mut pair_counts = Self::initial_pair_counts(&polymer);
-        let last_char = polymer.chars().last().unwrap();
+    type Input = (String, Vec<Rule>);
 
-        for _ in 0..10 {
-            pair_counts = Self::apply_rules(&pair_counts, &rules);
-        }
-        println!("After 10 iterations, diff of most/least common: {}",
-                 Self::calculate_solution(&pair_counts, last_char));
-        for _ in 0..30 {
-            pair_counts = Self::apply_rules(&pair_counts, &rules);
-        }
-        println!("After 40 iterations, diff of most/least common: {}",
-                 Self::calculate_solution(&pair_counts, last_char));
-        Ok(())
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        Self::read_input(input)
+    }
+
+    fn part1(&self, (polymer, rules): &Self::Input) -> Result<Answer, Error> {
+        Ok(Self::grow_polymer(polymer, rules, 10)?.into())
+    }
+
+    fn part2(&self, (polymer, rules): &Self::Input) -> Result<Answer, Error> {
+        Ok(Self::grow_polymer(polymer, rules, 40)?.into())
     }
 }
 
 impl Solver {
+    // Apply the rules for the given number of iterations, returning the
+    // difference between the most and least common elements.
+    fn grow_polymer(polymer: &str, rules: &[Rule], iterations: usize)
+            -> Result<usize, Error>
+    {
+        let mut pair_counts = Self::initial_pair_counts(polymer);
+        let last_char = polymer.chars().last()
+                               .ok_or(format_err!("Empty polymer"))?;
+
+        for _ in 0..iterations {
+            pair_counts = Self::apply_rules(&pair_counts, rules);
+        }
+        Ok(Self::calculate_solution(&pair_counts, last_char))
+    }
+
     fn apply_rules(pair_counts: &HashMap<(char, char), usize>,
-                   rules: &Vec<Rule>) -> HashMap<(char, char), usize>
+                   rules: &[Rule]) -> HashMap<(char, char), usize>
     {
         let mut new_counts = HashMap::new();
         for (&(c1, c2), count) in pair_counts.iter() {
@@ -71,30 +83,46 @@ impl Solver {
             *counts.entry(*c1).or_insert(0) += count;
         }
         *counts.entry(last_char).or_insert(0) += 1;
-        let max_count = counts.iter().map(|(_k, v)| v).max().unwrap();
-        let min_count = counts.iter().map(|(_k, v)| v).min().unwrap();
+        let max_count = counts.values().max().unwrap();
+        let min_count = counts.values().min().unwrap();
         max_count - min_count
     }
 
-    fn read_input(input_path: &str) -> Result<(String, Vec<Rule>), Error>
+    fn read_input(input: &mut dyn BufRead) -> Result<(String, Vec<Rule>), Error>
     {
-        let lines = io::read_lines_from_file(input_path)?;
-        let mut iter = lines.iter();
-        let polymer = iter.next().unwrap().to_string();
-        let mut rules = Vec::new();
-        if iter.next().unwrap() != "" {
-            return Err(format_err!("No separator after polymer"));
+        let lines = io::read_lines(input)?;
+        let mut iter = io::numbered(&lines);
+        let polymer = iter.next().ok_or(format_err!("Empty input"))?;
+        if let Some(i) = polymer.text.find(|c: char| !c.is_ascii_uppercase()) {
+            return Err(polymer.error_at(i + 1, "expected an element").into());
+        }
+        match iter.next() {
+            Some(line) if line.text.is_empty() => {},
+            Some(line) => {
+                return Err(line.error("expected a blank line after the \
+                                       polymer").into());
+            },
+            None => return Err(format_err!("No rules after polymer")),
         }
-        for line in iter {
-            let mut parts = line.split(" -> ");
-            let left = parts.next().unwrap();
-            let right = parts.next().unwrap();
-            rules.push(Rule {
-                c1: left.chars().nth(0).unwrap(),
-                c2: left.chars().nth(1).unwrap(),
-                i: right.chars().nth(0).unwrap(),
-            });
+        let rules = iter.map(|line| Ok(Self::parse_rule(line)?))
+                        .collect::<Result<Vec<Rule>, Error>>()?;
+        Ok((polymer.text.to_string(), rules))
+    }
+
+    // Parse a rule written "AB -> C".
+    fn parse_rule(line: Line) -> Result<Rule, ParseError>
+    {
+        let bad_rule = || line.error("expected a rule like AB -> C");
+        let (left, right) = line.text.split_once(" -> ").ok_or_else(bad_rule)?;
+        let left: Vec<char> = left.chars().collect();
+        let right: Vec<char> = right.chars().collect();
+        if left.len() != 2 || right.len() != 1 {
+            return Err(bad_rule());
         }
-        Ok((polymer, rules))
+        Ok(Rule {
+            c1: left[0],
+            c2: left[1],
+            i: right[0],
+        })
     }
 }
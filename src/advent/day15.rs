@@ -1,139 +1,227 @@
-use crate::advent::AdventSolver;
+use crate::advent::{AdventSolver, Answer};
+use crate::pos;
+use crate::shared::grid::{Grid, Pos, Renderable};
 use crate::shared::io;
+use crate::shared::search;
 use anyhow::{Error, format_err};
-use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
 
 #[derive(Default)]
 pub struct Solver;
 
-#[derive(Copy,Clone,Debug,Eq,Hash,PartialEq)]
-struct Pos {
-    row: usize,
-    col: usize,
-}
-
 impl AdventSolver for Solver {
-    fn solve(&mut self, input_path: &str) -> Result<(), Error> {
-        let grid = Self::read_grid(input_path)?;
-        let target = Pos {
-            row: grid.len()-1,
-            col: grid[0].len()-1,
-        };
-        let distance = Self::shortest_path_length(
-                           &grid, Pos { row: 0, col: 0 }, target);
-        println!("Shortest distance (original graph): {}", distance);
-
-        let grid = Self::enlarge_grid(&grid);
-        let target = Pos {
-            row: grid.len()-1,
-            col: grid[0].len()-1,
-        };
-        let distance = Self::shortest_path_length(
-                           &grid, Pos { row: 0, col: 0 }, target);
-        println!("Shortest distance (enlarged graph): {}", distance);
-        Ok(())
-    }
-}
+    type Input = Grid<u32>;
 
-impl Solver {
-    // Dijkstra (thanks, Wikipedia)
-    fn shortest_path_length(grid: &Vec<Vec<u32>>, start: Pos, end: Pos) -> u32
-    {
-        // 1. Mark all nodes unvisited
-        let mut unvisited: HashSet<Pos> = HashSet::new();
-        // 2. Create a tentative distance for each point
-        let mut tentative_distance: HashMap<Pos, u32> = HashMap::new();
-        for row in 0..grid.len() {
-            for col in 0..grid[0].len() {
-                tentative_distance.insert(Pos { row, col }, u32::MAX);
-                unvisited.insert(Pos { row, col });
-            }
+    fn parse_input(&self, input: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        let lines = io::read_lines(input)?;
+        let grid = Grid::from_digits(&lines)?;
+        if grid.width() == 0 {
+            return Err(format_err!("Empty grid"));
         }
-        *tentative_distance.get_mut(&start).unwrap() = 0;
-
-        // 3. Consider all unvisited neighbors of current point
-        let mut current = start;
-        while unvisited.contains(&end) {
-            let neighbors = Self::neighbors(grid, current);
-            for &neighbor in neighbors.iter() {
-                if unvisited.contains(&neighbor) {
-                    let distance = tentative_distance[&current] +
-                                   grid[neighbor.row][neighbor.col];
-                    if tentative_distance[&neighbor] > distance {
-                        tentative_distance.insert(neighbor, distance);
-                    }
-                }
-            }
-            unvisited.remove(&current);
-            match unvisited.iter()
-                           .min_by_key(|p| tentative_distance[p]) {
-                Some(node) => current = *node,
-                None => break,
+        // Every line is all digits by now, so byte offsets are columns.
+        for line in io::numbered(&lines) {
+            if let Some(i) = line.text.find('0') {
+                return Err(line.error_at(i + 1, "risk must be from 1 to 9")
+                               .into());
             }
         }
+        Ok(grid)
+    }
+
+    // Part 1: The original graph
+    fn part1(&self, grid: &Self::Input) -> Result<Answer, Error> {
+        Ok(Self::lowest_total_risk(&TiledMap::new(grid, 1))?.into())
+    }
 
-        tentative_distance[&end]
+    // Part 2: The graph enlarged 5x in each direction
+    fn part2(&self, grid: &Self::Input) -> Result<Answer, Error> {
+        Ok(Self::lowest_total_risk(&TiledMap::new(grid, 5))?.into())
     }
 
-    fn enlarge_grid(grid: &Vec<Vec<u32>>) -> Vec<Vec<u32>>
+    fn pictures(&self, grid: &Self::Input)
+            -> Result<Vec<(String, Answer)>, Error>
     {
-        let mut result = Vec::new();
-        for i in 0..5 {
-            for row in 0..grid.len() {
-                let mut new_row = Vec::new();
-                for j in 0..5 {
-                    for col in 0..grid[0].len() {
-                        let mut v = grid[row][col] + i + j;
-                        while v > 9 {
-                            v -= 9;
-                        }
-                        new_row.push(v);
-                    }
-                }
-                result.push(new_row);
-            }
-        }
-        result
+        let map = TiledMap::new(grid, 1);
+        let path = Self::shortest_path(&map, Pos::origin(),
+                                       map.bottom_right())?;
+        Ok(vec![("Least risky path".to_string(),
+                 Self::render_path(&map, &path))])
+    }
+}
+
+/* The risk map repeated factor times across and down, with risk one higher
+ * (wrapping from 9 back to 1) for each tile further right or down. Risks are
+ * computed on access, so large factors cost no extra memory. */
+pub struct TiledMap<'a> {
+    grid: &'a Grid<u32>,
+    factor: usize,
+}
+
+impl<'a> TiledMap<'a> {
+    fn new(grid: &'a Grid<u32>, factor: usize) -> TiledMap<'a> {
+        TiledMap { grid, factor }
+    }
+
+    fn width(&self) -> usize {
+        self.grid.width() * self.factor
+    }
+
+    fn height(&self) -> usize {
+        self.grid.height() * self.factor
+    }
+
+    fn bottom_right(&self) -> Pos {
+        pos!(self.height() as isize - 1, self.width() as isize - 1)
+    }
+
+    fn in_bounds(&self, pos: Pos) -> bool {
+        pos.row >= 0 && (pos.row as usize) < self.height() &&
+        pos.col >= 0 && (pos.col as usize) < self.width()
     }
 
-    fn neighbors(grid: &Vec<Vec<u32>>, pos: Pos) -> Vec<Pos>
+    fn risk(&self, pos: Pos) -> u32 {
+        let height = self.grid.height() as isize;
+        let width = self.grid.width() as isize;
+        let tile = (pos.row / height + pos.col / width) as u32;
+        let orig = pos!(pos.row % height, pos.col % width);
+        (self.grid[orig] - 1 + tile) % 9 + 1
+    }
+
+    fn neighbors(&self, pos: Pos) -> impl Iterator<Item=Pos> + '_ {
+        pos.neighbors4().filter(move |&n| self.in_bounds(n))
+    }
+
+    // Row-major numbering of positions, for the dense-array Dijkstra.
+    fn index_of(&self, pos: Pos) -> usize {
+        pos.row as usize * self.width() + pos.col as usize
+    }
+
+    fn pos_of(&self, index: usize) -> Pos {
+        pos!((index / self.width()) as isize, (index % self.width()) as isize)
+    }
+}
+
+impl Solver {
+    // Total risk of the least risky path across the map, from the top left
+    // to the bottom right.
+    fn lowest_total_risk(map: &TiledMap) -> Result<u32, Error> {
+        let path = Self::shortest_path(map, Pos::origin(), map.bottom_right())?;
+        Ok(Self::path_weight(map, &path))
+    }
+
+    // The least risky path from start to end, inclusive, found with the
+    // dense-array Dijkstra, since map positions are easy to number. Checked
+    // against the length the search found, since only the path is used for
+    // answers.
+    fn shortest_path(map: &TiledMap, start: Pos, end: Pos)
+            -> Result<Vec<Pos>, Error>
     {
-        let mut neighbors = Vec::new();
-        if pos.row > 0 {
-            neighbors.push(Pos { row: pos.row-1, col: pos.col });
-        }
-        if pos.col > 0 {
-            neighbors.push(Pos { row: pos.row, col: pos.col-1 });
+        let target = map.index_of(end);
+        let paths = search::dense_dijkstra(
+            map.width() * map.height(),
+            map.index_of(start),
+            Some(target),
+            |node| map.neighbors(map.pos_of(node))
+                      .map(|n| (map.index_of(n), map.risk(n) as u64)));
+        let (length, path) =
+            paths.distance(target)
+                 .zip(paths.path_to(target))
+                 .ok_or(format_err!("No path from {:?} to {:?}", start, end))?;
+        let path: Vec<Pos> = path.into_iter()
+                                 .map(|node| map.pos_of(node))
+                                 .collect();
+        let steps_ok = path.windows(2)
+                           .all(|w| w[0].manhattan_distance(&w[1]) == 1);
+        if !steps_ok || Self::path_weight(map, &path) as u64 != length {
+            return Err(format_err!("Path does not match length {}", length));
         }
-        if pos.row < grid.len() - 1 {
-            neighbors.push(Pos { row: pos.row+1, col: pos.col });
-        }
-        if pos.col < grid[0].len() - 1 {
-            neighbors.push(Pos { row: pos.row, col: pos.col+1 });
-        }
-        neighbors
+        Ok(path)
     }
 
-    fn path_weight(grid: &Vec<Vec<u32>>, path: &Vec<Pos>) -> u32
+    // Total risk of entering each cell of path after the first.
+    fn path_weight(map: &TiledMap, path: &[Pos]) -> u32
     {
         path.iter()
-            .map(|p| grid[p.row][p.col])
+            .skip(1)
+            .map(|&p| map.risk(p))
             .sum()
     }
 
-    fn read_grid(input_path: &str) -> Result<Vec<Vec<u32>>, Error>
+    // Draw the risk map, with the cells along path drawn as '*'.
+    fn render_path(map: &TiledMap, path: &[Pos]) -> Answer
     {
-        let lines = io::read_lines_from_file(input_path)?;
-        let mut result = Vec::new();
-        for line in lines.iter() {
-            let mut row = Vec::new();
-            for c in line.chars() {
-                row.push(c.to_digit(10)
-                          .ok_or(format_err!("Bad digit: {}", c))?);
-            }
-            result.push(row);
+        let mut cells = Grid::new(map.width(), map.height(), (0, false));
+        for (pos, cell) in cells.iter_mut() {
+            *cell = (map.risk(pos), false);
+        }
+        for &pos in path.iter() {
+            cells[pos].1 = true;
         }
+        let lines = cells.render_with(|&(risk, on_path)| {
+                             if on_path {
+                                 '*'
+                             } else {
+                                 char::from_digit(risk, 10).unwrap_or('+')
+                             }
+                         })
+                         .lines();
+        Answer::Grid(lines)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Solver, TiledMap};
+    use crate::advent::{AdventSolver, Answer};
+    use crate::advent::bench::Timings;
+    use crate::pos;
+    use crate::shared::grid::{Grid, Pos};
+    use crate::shared::io::{self, ParseError};
+
+    #[test]
+    fn tiles_wrap_risk() {
+        let grid: Grid<u32> = Grid::from_digits(&["18"]).unwrap();
+        let map = TiledMap::new(&grid, 5);
+        assert_eq!((10, 5), (map.width(), map.height()));
+        let row: Vec<u32> = (0..10).map(|c| map.risk(pos!(0, c))).collect();
+        assert_eq!(vec![1, 8, 2, 9, 3, 1, 4, 2, 5, 3], row);
+        assert_eq!(7, map.risk(pos!(4, 9)));
+        assert!(!map.in_bounds(pos!(5, 0)));
+    }
+
+    #[test]
+    fn rejects_zero_risk() {
+        let error = Solver.parse_input(&mut "19\n10\n".as_bytes())
+                          .expect_err("Input should not parse");
+        let error = error.downcast::<ParseError>().unwrap();
+        assert_eq!((2, 2), (error.line, error.column));
+    }
+
+    #[test]
+    fn renders_path_over_risks() {
+        let grid: Grid<u32> = Grid::from_digits(&["19", "11"]).unwrap();
+        let map = TiledMap::new(&grid, 1);
+        let path = Solver::shortest_path(&map, Pos::origin(),
+                                         map.bottom_right()).unwrap();
+        assert_eq!(Answer::Grid(vec!["*9".to_string(), "**".to_string()]),
+                   Solver::render_path(&map, &path));
+    }
 
-        Ok(result)
+    // Times part 2's enlarged map. Slow in debug builds, so run it with:
+    //   cargo test --release bench_enlarged_grid -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_enlarged_grid() {
+        let lines = io::read_lines_from_file("input/day15.txt").unwrap();
+        let grid: Grid<u32> = Grid::from_digits(&lines).unwrap();
+        let map = TiledMap::new(&grid, 5);
+        let mut timings = Timings::new("dijkstra");
+        let path = timings.measure(10, || {
+            Solver::shortest_path(&map, Pos::origin(), map.bottom_right())
+        }).unwrap();
+        assert_eq!(2899, Solver::path_weight(&map, &path));
+        println!("{}x{} map: min {:.2?}, median {:.2?}, mean {:.2?}",
+                 map.width(), map.height(),
+                 timings.min(), timings.median(), timings.mean());
     }
 }
@@ -1,5 +1,176 @@
+pub mod answers;
+pub mod bench;
+
+use anyhow::format_err;
+use bench::Timings;
+use crate::shared::io;
+use std::fmt;
+use std::io::BufRead;
+
+// Each day reads its input once, then solves both parts from the parsed
+// result.
 trait AdventSolver {
-    fn solve(&mut self, input_path: &str) -> Result<(), anyhow::Error>;
+    type Input;
+
+    fn parse_input(&self, input: &mut dyn BufRead)
+        -> Result<Self::Input, anyhow::Error>;
+    fn part1(&self, input: &Self::Input) -> Result<Answer, anyhow::Error>;
+    fn part2(&self, input: &Self::Input) -> Result<Answer, anyhow::Error>;
+
+    // Labelled pictures of the puzzle state, shown with --verbose. Most days
+    // have nothing to draw.
+    fn pictures(&self, _input: &Self::Input)
+            -> Result<Vec<(String, Answer)>, anyhow::Error>
+    {
+        Ok(Vec::new())
+    }
+}
+
+// The answers to the parts of a day's puzzle that were run.
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Solution {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+    // Labelled pictures of the puzzle state, if they were asked for.
+    pub pictures: Vec<(String, Answer)>,
+}
+
+// A single puzzle answer. Most are numbers, some are text, and a few are
+// pictures that have to be read by a human.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    Grid(Vec<String>),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Grid(rows) => {
+                for row in rows.iter() {
+                    write!(f, "\n{}", row)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+// Every integer type converted here fits in an i128, so no answer wraps.
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    }
+}
+
+answer_from_int!(i32, i64, isize, u8, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+// Object-safe interface to a day's solver, so days can be looked up at
+// runtime.
+trait Runner {
+    fn run(&self, input_path: &str, part: Option<usize>, pictures: bool)
+        -> Result<Solution, anyhow::Error>;
+    fn bench(&self, input_path: &str, part: Option<usize>, iterations: usize)
+        -> Result<Vec<Timings>, anyhow::Error>;
+}
+
+impl<S: AdventSolver> Runner for S {
+    // Parse the input and run the requested part (or both parts, if None),
+    // drawing the solver's pictures too if asked.
+    fn run(&self, input_path: &str, part: Option<usize>, pictures: bool)
+            -> Result<Solution, anyhow::Error>
+    {
+        let text = io::read_input(input_path)?;
+        let input = self.parse_input(&mut text.as_bytes())
+                        .map_err(|e| io::attach_path(e, input_path))?;
+        let mut solution = Solution::default();
+        if part.is_none() || part == Some(1) {
+            solution.part1 = Some(self.part1(&input)?);
+        }
+        if part.is_none() || part == Some(2) {
+            solution.part2 = Some(self.part2(&input)?);
+        }
+        if pictures {
+            solution.pictures = self.pictures(&input)?;
+        }
+        Ok(solution)
+    }
+
+    // Time the parse step and the requested parts, each run separately. The
+    // input is only read once, so reading the file isn't included.
+    fn bench(&self, input_path: &str, part: Option<usize>, iterations: usize)
+            -> Result<Vec<Timings>, anyhow::Error>
+    {
+        let text = io::read_input(input_path)?;
+        let mut parse = Timings::new("parse");
+        let input = parse.measure(iterations,
+                                  || self.parse_input(&mut text.as_bytes()))
+                         .map_err(|e| io::attach_path(e, input_path))?;
+        let mut timings = vec![parse];
+        if part.is_none() || part == Some(1) {
+            let mut part1 = Timings::new("part1");
+            part1.measure(iterations, || self.part1(&input))?;
+            timings.push(part1);
+        }
+        if part.is_none() || part == Some(2) {
+            let mut part2 = Timings::new("part2");
+            part2.measure(iterations, || self.part2(&input))?;
+            timings.push(part2);
+        }
+        Ok(timings)
+    }
+}
+
+// Path of the input file for the given day. Examples from the problem text
+// are named dayNN_ex.txt, with any further ones numbered (dayNN_ex2.txt) or
+// named (dayNN_ex_large.txt).
+pub fn input_path(day: usize, example: Option<&str>) -> String {
+    match example {
+        None => format!("input/day{:02}.txt", day),
+        Some("") | Some("1") => format!("input/day{:02}_ex.txt", day),
+        Some(name) if name.chars().all(|c| c.is_ascii_digit()) => {
+            format!("input/day{:02}_ex{}.txt", day, name)
+        },
+        Some(name) => format!("input/day{:02}_ex_{}.txt", day, name),
+    }
+}
+
+// Run the solver for the given day on the input at input_path ("-" for
+// stdin), including its pictures if pictures is set.
+pub fn solve(day: usize, input_path: &str, part: Option<usize>,
+             pictures: bool) -> Result<Solution, anyhow::Error>
+{
+    solver(day).ok_or(format_err!("No solver for day {}", day))?
+               .run(input_path, part, pictures)
+}
+
+// Run the solver for the given day repeatedly, timing each phase.
+pub fn bench(day: usize, input_path: &str, part: Option<usize>,
+             iterations: usize) -> Result<Vec<Timings>, anyhow::Error>
+{
+    solver(day).ok_or(format_err!("No solver for day {}", day))?
+               .bench(input_path, part, iterations)
 }
 
 // Generated by build.rs
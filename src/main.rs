@@ -3,29 +3,296 @@
 mod advent;
 mod shared;
 
+use advent::answers::{Check, KnownAnswers, ANSWERS_PATH};
+use advent::bench::Timings;
+use advent::{Answer, Solution};
 use argparse::{ArgumentParser, StoreOption, StoreTrue};
+use serde_json::json;
+use std::process;
+use std::time::{Duration, Instant};
+
+// Outcome of running one day, for the summary table.
+struct DayReport {
+    day: usize,
+    solution: Option<Solution>,
+    elapsed: Duration,
+    status: String,
+}
 
 fn main() {
-    let mut day: Option<usize> = None;
+    let mut day: Option<String> = None;
+    let mut all: bool = false;
     let mut use_example: bool = false;
+    let mut example: Option<String> = None;
+    let mut input: Option<String> = None;
+    let mut part: Option<usize> = None;
+    let mut check: bool = false;
+    let mut bench: Option<usize> = None;
+    let mut json: bool = false;
+    let mut verbose: bool = false;
     {
         let mut parser = ArgumentParser::new();
-        parser.set_description("Advent of Code 2019");
+        parser.set_description("Advent of Code 2021");
         parser.refer(&mut day)
               .add_option(&["-d", "--day"], StoreOption,
-                          "number of challenge to run");
+                          "number of challenge to run, or a range (e.g. 3-9)");
+        parser.refer(&mut all)
+              .add_option(&["-a", "--all"], StoreTrue,
+                          "Run every day");
         parser.refer(&mut use_example)
-              .add_option(&["-e", "--example"], StoreTrue,
+              .add_option(&["-e"], StoreTrue,
                           "Use the example input from the problem");
+        parser.refer(&mut example)
+              .add_option(&["--example"], StoreOption,
+                          "Use the named example input, e.g. 2 for \
+                           dayNN_ex2.txt or large for dayNN_ex_large.txt");
+        parser.refer(&mut input)
+              .add_option(&["-i", "--input"], StoreOption,
+                          "Path of the input file to use (- for stdin)");
+        parser.refer(&mut part)
+              .add_option(&["-p", "--part"], StoreOption,
+                          "Only run the given part (1 or 2)");
+        parser.refer(&mut check)
+              .add_option(&["-c", "--check"], StoreTrue,
+                          "Compare the results against the known answers");
+        parser.refer(&mut bench)
+              .add_option(&["-b", "--bench"], StoreOption,
+                          "Time N runs of each phase instead of solving once");
+        parser.refer(&mut json)
+              .add_option(&["-j", "--json"], StoreTrue,
+                          "Print benchmark results as JSON");
+        parser.refer(&mut verbose)
+              .add_option(&["-v", "--verbose"], StoreTrue,
+                          "Also print pictures of the puzzle state, for days \
+                           that draw any");
         parser.parse_args_or_exit();
     }
-    match day {
-        Some(ref day) => {
-            match advent::solve(*day, use_example) {
-                Ok(_) => {},
-                Err(e) => println!("error: {}", e)
+    if part.is_some() && part != Some(1) && part != Some(2) {
+        println!("--part must be 1 or 2");
+        process::exit(2);
+    }
+    let days = match (all, day) {
+        (true, _) => advent::DAYS.to_vec(),
+        (false, Some(ref spec)) => match parse_days(spec) {
+            Some(days) => days,
+            None => {
+                println!("Bad --day: {}", spec);
+                process::exit(2);
+            },
+        },
+        (false, None) => {
+            println!("--day or --all is required");
+            process::exit(2);
+        },
+    };
+    if use_example && example.is_none() {
+        example = Some("1".to_string());
+    }
+    if input.is_some() && (days.len() != 1 || example.is_some()) {
+        println!("--input needs a single --day and no --example");
+        process::exit(2);
+    }
+    // The input for each day, unless given explicitly.
+    let input_path = |day| {
+        input.clone()
+             .unwrap_or_else(|| advent::input_path(day, example.as_deref()))
+    };
+
+    if let Some(iterations) = bench {
+        if iterations == 0 {
+            println!("--bench must be at least 1");
+            process::exit(2);
+        }
+        run_benchmarks(&days, input_path, part, iterations, json);
+        return;
+    }
+    let answers = if check {
+        match KnownAnswers::load(ANSWERS_PATH) {
+            Ok(answers) => Some(answers),
+            Err(e) => {
+                println!("error: {}", e);
+                process::exit(2);
+            },
+        }
+    } else {
+        None
+    };
+
+    let mut reports = Vec::new();
+    for &day in days.iter() {
+        if days.len() > 1 {
+            println!("== Day {} ==", day);
+        }
+        reports.push(run_day(day, &input_path(day), part, verbose,
+                             answers.as_ref()));
+    }
+    if days.len() > 1 {
+        println!();
+        print_summary(&reports);
+    }
+    if reports.iter().any(|r| r.status != "ok") {
+        process::exit(1);
+    }
+}
+
+// A single day ("5") or an inclusive range of days ("3-9"). Ranges only
+// include days that have a solver, and must include at least one.
+fn parse_days(spec: &str) -> Option<Vec<usize>> {
+    match spec.split_once('-') {
+        Some((first, last)) => {
+            let first = first.trim().parse::<usize>().ok()?;
+            let last = last.trim().parse::<usize>().ok()?;
+            let days: Vec<usize> =
+                advent::DAYS.iter()
+                            .filter(|&&day| day >= first && day <= last)
+                            .cloned()
+                            .collect();
+            if days.is_empty() { None } else { Some(days) }
+        },
+        None => Some(vec![spec.trim().parse::<usize>().ok()?]),
+    }
+}
+
+// Run one day, printing its answers (and its pictures, and the result of
+// checking the answers, if requested).
+fn run_day(day: usize, input_path: &str, part: Option<usize>, verbose: bool,
+           answers: Option<&KnownAnswers>) -> DayReport
+{
+    let start = Instant::now();
+    let result = advent::solve(day, input_path, part, verbose);
+    let elapsed = start.elapsed();
+    let mut report = DayReport {
+        day,
+        solution: None,
+        elapsed,
+        status: "ok".to_string(),
+    };
+    match result {
+        Ok(solution) => {
+            if let Some(ref answer) = solution.part1 {
+                println!("Part 1: {}", answer);
             }
+            if let Some(ref answer) = solution.part2 {
+                println!("Part 2: {}", answer);
+            }
+            for (label, picture) in solution.pictures.iter() {
+                println!("{}: {}", label, picture);
+            }
+            if let Some(answers) = answers {
+                if !check_solution(answers, input_path, &solution) {
+                    report.status = "WRONG".to_string();
+                }
+            }
+            report.solution = Some(solution);
         },
-        None => println!("--day is required"),
+        Err(e) => {
+            println!("error: {}", e);
+            report.status = format!("error: {}", e);
+        },
+    }
+    report
+}
+
+// Compare a solution against the known answers, printing any differences.
+// Returns false if any part was wrong.
+fn check_solution(answers: &KnownAnswers, input_path: &str,
+                  solution: &Solution) -> bool
+{
+    let mut ok = true;
+    for (part, check) in answers.check(input_path, solution) {
+        match check {
+            Check::Correct => println!("Part {}: correct", part),
+            Check::Unknown => println!("Part {}: no known answer", part),
+            Check::Wrong { expected, actual } => {
+                println!("Part {}: MISMATCH", part);
+                println!("  expected: {}", expected);
+                println!("  actual:   {}", actual);
+                ok = false;
+            },
+        }
+    }
+    ok
+}
+
+fn print_summary(reports: &[DayReport]) {
+    // Grids don't fit in a table cell; they were printed in full above.
+    let cell = |answer: &Option<Answer>| match answer {
+        Some(Answer::Grid(_)) => "(grid)".to_string(),
+        Some(answer) => answer.to_string(),
+        None => "-".to_string(),
+    };
+    let mut rows = vec![["Day".to_string(), "Part 1".to_string(),
+                         "Part 2".to_string(), "Time".to_string(),
+                         "Status".to_string()]];
+    for report in reports.iter() {
+        let (part1, part2) = match report.solution {
+            Some(ref solution) => (cell(&solution.part1), cell(&solution.part2)),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        rows.push([report.day.to_string(), part1, part2,
+                   format!("{:.2?}", report.elapsed), report.status.clone()]);
+    }
+    let mut widths = [0; 5];
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in rows.iter() {
+        let line = row.iter()
+                      .zip(widths.iter())
+                      .map(|(cell, width)| format!("{:<1$}", cell, width))
+                      .collect::<Vec<String>>()
+                      .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
+
+// Benchmark each day, then print the timings of every phase as a table or
+// JSON.
+fn run_benchmarks<F>(days: &[usize], input_path: F, part: Option<usize>,
+                     iterations: usize, json: bool)
+where
+    F: Fn(usize) -> String
+{
+    let mut results: Vec<(usize, Timings)> = Vec::new();
+    let mut failed = false;
+    for &day in days.iter() {
+        match advent::bench(day, &input_path(day), part, iterations) {
+            Ok(timings) => {
+                results.extend(timings.into_iter().map(|t| (day, t)));
+            },
+            Err(e) => {
+                eprintln!("day {}: error: {}", day, e);
+                failed = true;
+            },
+        }
+    }
+
+    if json {
+        let phases: Vec<serde_json::Value> =
+            results.iter()
+                   .map(|(day, t)| json!({
+                       "day": day,
+                       "phase": t.phase,
+                       "iterations": t.iterations(),
+                       "min_ns": t.min().as_nanos() as u64,
+                       "median_ns": t.median().as_nanos() as u64,
+                       "mean_ns": t.mean().as_nanos() as u64,
+                   }))
+                   .collect();
+        println!("{}", serde_json::to_string_pretty(&phases).unwrap());
+    } else {
+        println!("{:<5}{:<7}{:>12}{:>12}{:>12}", "Day", "Phase",
+                 "Min", "Median", "Mean");
+        for (day, t) in results.iter() {
+            println!("{:<5}{:<7}{:>12}{:>12}{:>12}", day, t.phase,
+                     format!("{:.2?}", t.min()),
+                     format!("{:.2?}", t.median()),
+                     format!("{:.2?}", t.mean()));
+        }
+    }
+    if failed {
+        process::exit(1);
     }
 }
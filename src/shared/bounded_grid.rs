@@ -0,0 +1,232 @@
+use super::grid::Pos;
+use super::io::{self, ParseError};
+use anyhow::{Error, format_err};
+use std::ops::{Index,IndexMut};
+
+/* A finite 2-dimensional grid with rows 0..height and columns 0..width,
+ * such as the character maps many puzzles use as input. */
+#[derive(Clone,Debug,PartialEq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+        where T: Clone
+    {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    // Build a grid from its rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, Error> {
+        let height = rows.len();
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * height);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(format_err!("Row {} has length {}, expected {}",
+                                       i, row.len(), width));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid { cells, width, height })
+    }
+
+    // Parse a grid of digits, one row per line.
+    pub fn from_digits<S: AsRef<str>>(lines: &[S]) -> Result<Grid<T>, Error>
+        where T: From<u8>
+    {
+        Self::check_widths(lines)?;
+        Self::from_rows(
+            io::numbered(lines)
+                .map(|line| {
+                    line.text
+                        .chars()
+                        .enumerate()
+                        .map(|(i, c)| {
+                            c.to_digit(10)
+                             .map(|d| T::from(d as u8))
+                             .ok_or_else(|| line.error_at(
+                                 i + 1, format!("expected a digit, found {:?}",
+                                                c)))
+                        })
+                        .collect::<Result<Vec<T>, ParseError>>()
+                })
+                .collect::<Result<Vec<Vec<T>>, ParseError>>()?)
+    }
+
+    // Check that every line has as many characters as the first.
+    fn check_widths<S: AsRef<str>>(lines: &[S]) -> Result<(), ParseError> {
+        let mut lines = io::numbered(lines);
+        let width = match lines.next() {
+            Some(first) => first.text.chars().count(),
+            None => return Ok(()),
+        };
+        for line in lines {
+            let len = line.text.chars().count();
+            if len != width {
+                return Err(line.error_at(
+                    len.min(width) + 1,
+                    format!("row has length {}, expected {}", len, width)));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, pos: Pos) -> bool {
+        pos.row >= 0 && (pos.row as usize) < self.height &&
+        pos.col >= 0 && (pos.col as usize) < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.in_bounds(pos) {
+            Some(&self.cells[self.index_of(pos)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.in_bounds(pos) {
+            let index = self.index_of(pos);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, row: usize) -> impl Iterator<Item=&T> {
+        self.cells[row*self.width..(row+1)*self.width].iter()
+    }
+
+    pub fn col(&self, col: usize) -> impl Iterator<Item=&T> {
+        assert!(col < self.width, "column {} is outside the grid", col);
+        self.cells[col..].iter().step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item=&[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    // Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item=Pos> {
+        let width = self.width;
+        (0..self.width * self.height)
+            .map(move |i| Pos::new((i / width) as isize, (i % width) as isize))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item=(Pos, &mut T)> {
+        let width = self.width;
+        self.cells.iter_mut()
+                  .enumerate()
+                  .map(move |(i, v)| {
+                      (Pos::new((i / width) as isize, (i % width) as isize), v)
+                  })
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item=&mut T> {
+        self.cells.iter_mut()
+    }
+
+    // The orthogonal neighbors of pos that are within the grid.
+    pub fn neighbors(&self, pos: Pos) -> impl Iterator<Item=Pos> + '_ {
+        pos.neighbors4().filter(move |&n| self.in_bounds(n))
+    }
+
+    // The neighbors of pos, including diagonals, that are within the grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item=Pos> + '_ {
+        pos.neighbors8().filter(move |&n| self.in_bounds(n))
+    }
+
+    fn index_of(&self, pos: Pos) -> usize {
+        pos.row as usize * self.width + pos.col as usize
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", pos))
+    }
+}
+
+impl Grid<char> {
+    // Parse a grid of characters, one row per line.
+    pub fn from_chars<S: AsRef<str>>(lines: &[S]) -> Result<Grid<char>, Error>
+    {
+        Self::check_widths(lines)?;
+        Self::from_rows(lines.iter()
+                             .map(|line| line.as_ref().chars().collect())
+                             .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use super::super::grid::Pos;
+    use super::super::io::ParseError;
+    use crate::pos;
+
+    #[test]
+    fn parses_digits() {
+        let grid: Grid<u32> = Grid::from_digits(&["123", "456"]).unwrap();
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!(6, grid[pos!(1, 2)]);
+        assert_eq!(vec![&2, &5], grid.col(1).collect::<Vec<_>>());
+        assert_eq!(vec![&4, &5, &6], grid.row(1).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn rejects_uneven_rows() {
+        assert!(Grid::from_chars(&["ab", "c"]).is_err());
+        let error = Grid::<u8>::from_digits(&["12", "3x"]).unwrap_err();
+        let error = error.downcast::<ParseError>().unwrap();
+        assert_eq!((2, 2), (error.line, error.column));
+    }
+
+    #[test]
+    fn neighbors_stay_in_bounds() {
+        let grid = Grid::new(3, 3, 0);
+        assert!(!grid.in_bounds(pos!(-1, 0)));
+        assert!(!grid.in_bounds(pos!(0, 3)));
+        assert_eq!(2, grid.neighbors(pos!(0, 0)).count());
+        assert_eq!(4, grid.neighbors(pos!(1, 1)).count());
+        assert_eq!(None, grid.get(pos!(3, 0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the grid")]
+    fn col_past_right_edge_panics() {
+        let grid: Grid<u8> = Grid::from_digits(&["12", "34"]).unwrap();
+        grid.col(2).for_each(drop);
+    }
+}
@@ -0,0 +1,109 @@
+// Dijkstra's shortest paths over densely numbered nodes, using a binary heap
+// for the frontier and plain Vecs for per-node state.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// Marks a node with no predecessor: the start, or one not reached.
+const NO_PARENT: usize = usize::MAX;
+
+// Distances from the start node to each node 0..node_count, found by
+// dijkstra(), along with the predecessor of each node on its shortest path.
+// Only settled nodes, whose distances are final, count as reached.
+pub struct ShortestPaths {
+    distances: Vec<u64>,
+    parents: Vec<usize>,
+    settled: Vec<bool>,
+}
+
+impl ShortestPaths {
+    // The length of the shortest path to node, or None if it wasn't reached.
+    pub fn distance(&self, node: usize) -> Option<u64> {
+        match self.settled.get(node) {
+            Some(true) => Some(self.distances[node]),
+            _ => None,
+        }
+    }
+
+    // The nodes along the shortest path from the start to node, inclusive,
+    // or None if node wasn't reached.
+    pub fn path_to(&self, node: usize) -> Option<Vec<usize>> {
+        self.distance(node)?;
+        let mut path = vec![node];
+        let mut current = node;
+        while self.parents[current] != NO_PARENT {
+            current = self.parents[current];
+            path.push(current);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// Find shortest paths from start over nodes numbered 0..node_count, where
+// edges(node) gives each (neighbor, cost) leaving node. Stops early once
+// target, if given, is settled; nodes not settled by then count as
+// unreached, even if a (possibly longer) path to them was seen.
+pub fn dijkstra<F, I>(node_count: usize, start: usize, target: Option<usize>,
+                      mut edges: F) -> ShortestPaths
+    where F: FnMut(usize) -> I,
+          I: IntoIterator<Item=(usize, u64)>
+{
+    let mut distances = vec![u64::MAX; node_count];
+    let mut parents = vec![NO_PARENT; node_count];
+    let mut settled = vec![false; node_count];
+    let mut frontier = BinaryHeap::new();
+    distances[start] = 0;
+    frontier.push(Reverse((0, start)));
+
+    while let Some(Reverse((distance, node))) = frontier.pop() {
+        if settled[node] {
+            // A stale entry, superseded by a shorter path found later
+            continue;
+        }
+        settled[node] = true;
+        if Some(node) == target {
+            break;
+        }
+        for (neighbor, cost) in edges(node) {
+            let candidate = distance + cost;
+            if !settled[neighbor] && candidate < distances[neighbor] {
+                distances[neighbor] = candidate;
+                parents[neighbor] = node;
+                frontier.push(Reverse((candidate, neighbor)));
+            }
+        }
+    }
+
+    ShortestPaths { distances, parents, settled }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dijkstra;
+
+    #[test]
+    fn finds_cheapest_route() {
+        // 0 -> 1 -> 3 costs 2, 0 -> 2 -> 3 costs 6; 4 is unreachable
+        let edges = [vec![(1, 1), (2, 1)], vec![(3, 1)], vec![(3, 5)],
+                     vec![], vec![(0, 1)]];
+        let paths = dijkstra(5, 0, None, |node| edges[node].clone());
+        assert_eq!(Some(0), paths.distance(0));
+        assert_eq!(Some(2), paths.distance(3));
+        assert_eq!(None, paths.distance(4));
+        assert_eq!(Some(vec![0, 1, 3]), paths.path_to(3));
+        assert_eq!(Some(vec![0]), paths.path_to(0));
+        assert_eq!(None, paths.path_to(4));
+    }
+
+    #[test]
+    fn stops_at_target() {
+        let edges = [vec![(1, 1), (2, 1)], vec![(3, 1)], vec![(3, 5)],
+                     vec![], vec![(0, 1)]];
+        let paths = dijkstra(5, 0, Some(1), |node| edges[node].clone());
+        assert_eq!(Some(vec![0, 1]), paths.path_to(1));
+        // 2 was seen but not settled, so its distance isn't final.
+        assert_eq!(None, paths.distance(2));
+        assert_eq!(None, paths.path_to(2));
+    }
+}
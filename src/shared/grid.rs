@@ -1,6 +1,10 @@
 // Cartesian grid structures and utilities.
 
+pub use super::bounded_grid::Grid;
 pub use super::infinite_grid::InfiniteGrid;
+pub use super::render::Renderable;
+pub use super::sparse_grid::SparseGrid;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
 use std::slice::Iter;
 
 #[derive(Clone,Copy,Debug,Eq,PartialEq)]
@@ -25,28 +29,119 @@ impl Dir {
 
     pub fn turn_right(&self) -> Dir {
         match self {
-            &Dir::Up    => Dir::Right,
-            &Dir::Right => Dir::Down,
-            &Dir::Down  => Dir::Left,
-            &Dir::Left  => Dir::Up,
+            Dir::Up    => Dir::Right,
+            Dir::Right => Dir::Down,
+            Dir::Down  => Dir::Left,
+            Dir::Left  => Dir::Up,
         }
     }
 
     pub fn turn_left(&self) -> Dir {
         match self {
-            &Dir::Up    => Dir::Left,
-            &Dir::Right => Dir::Up,
-            &Dir::Down  => Dir::Right,
-            &Dir::Left  => Dir::Down,
+            Dir::Up    => Dir::Left,
+            Dir::Right => Dir::Up,
+            Dir::Down  => Dir::Right,
+            Dir::Left  => Dir::Down,
         }
     }
 
     pub fn reverse(&self) -> Dir {
         match self {
-            &Dir::Up    => Dir::Down,
-            &Dir::Right => Dir::Left,
-            &Dir::Down  => Dir::Up,
-            &Dir::Left  => Dir::Right,
+            Dir::Up    => Dir::Down,
+            Dir::Right => Dir::Left,
+            Dir::Down  => Dir::Up,
+            Dir::Left  => Dir::Right,
+        }
+    }
+}
+
+// Directions including diagonals, in clockwise order. Turning moves by 45
+// degrees.
+#[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+#[allow(dead_code)]
+impl Dir8 {
+    pub fn iter() -> Iter<'static, Dir8> {
+        static DIRECTIONS: [Dir8; 8] = [
+            Dir8::Up,
+            Dir8::UpRight,
+            Dir8::Right,
+            Dir8::DownRight,
+            Dir8::Down,
+            Dir8::DownLeft,
+            Dir8::Left,
+            Dir8::UpLeft,
+        ];
+        DIRECTIONS.iter()
+    }
+
+    pub fn turn_right(&self) -> Dir8 {
+        match self {
+            Dir8::Up        => Dir8::UpRight,
+            Dir8::UpRight   => Dir8::Right,
+            Dir8::Right     => Dir8::DownRight,
+            Dir8::DownRight => Dir8::Down,
+            Dir8::Down      => Dir8::DownLeft,
+            Dir8::DownLeft  => Dir8::Left,
+            Dir8::Left      => Dir8::UpLeft,
+            Dir8::UpLeft    => Dir8::Up,
+        }
+    }
+
+    pub fn turn_left(&self) -> Dir8 {
+        match self {
+            Dir8::Up        => Dir8::UpLeft,
+            Dir8::UpRight   => Dir8::Up,
+            Dir8::Right     => Dir8::UpRight,
+            Dir8::DownRight => Dir8::Right,
+            Dir8::Down      => Dir8::DownRight,
+            Dir8::DownLeft  => Dir8::Down,
+            Dir8::Left      => Dir8::DownLeft,
+            Dir8::UpLeft    => Dir8::Left,
+        }
+    }
+
+    pub fn reverse(&self) -> Dir8 {
+        self.turn_right().turn_right().turn_right().turn_right()
+    }
+
+    // Change in (row, col) from taking one step in this direction.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Dir8::Up        => (-1,  0),
+            Dir8::UpRight   => (-1,  1),
+            Dir8::Right     => ( 0,  1),
+            Dir8::DownRight => ( 1,  1),
+            Dir8::Down      => ( 1,  0),
+            Dir8::DownLeft  => ( 1, -1),
+            Dir8::Left      => ( 0, -1),
+            Dir8::UpLeft    => (-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        let (row, col) = self.offset();
+        row != 0 && col != 0
+    }
+}
+
+impl From<Dir> for Dir8 {
+    fn from(dir: Dir) -> Dir8 {
+        match dir {
+            Dir::Up    => Dir8::Up,
+            Dir::Right => Dir8::Right,
+            Dir::Down  => Dir8::Down,
+            Dir::Left  => Dir8::Left,
         }
     }
 }
@@ -67,8 +162,8 @@ macro_rules! pos {
 impl Pos {
     pub fn new(row: isize, col: isize) -> Self {
         Pos {
-            row: row,
-            col: col
+            row,
+            col
         }
     }
 
@@ -80,6 +175,11 @@ impl Pos {
         ((self.row - other.row).abs() + (self.col - other.col).abs()) as usize
     }
 
+    // Number of king's moves between the positions.
+    pub fn chebyshev_distance(&self, other: &Pos) -> usize {
+        (self.row - other.row).abs().max((self.col - other.col).abs()) as usize
+    }
+
     pub fn neighbor(&self, dir: Dir) -> Pos {
         match dir {
             Dir::Up    => Pos::new(self.row-1, self.col),
@@ -88,4 +188,236 @@ impl Pos {
             Dir::Left  => Pos::new(self.row,   self.col-1),
         }
     }
+    // Each coordinate's sign: -1, 0 or 1.
+    pub fn signum(&self) -> Pos {
+        Pos::new(self.row.signum(), self.col.signum())
+    }
+
+    // One step (possibly diagonal) from here toward target, or staying put
+    // if already there.
+    pub fn step_toward(&self, target: &Pos) -> Pos {
+        *self + (*target - *self).signum()
+    }
+
+    // Rotate 90 degrees clockwise about the origin, as seen with rows going
+    // down the screen.
+    pub fn rotate_right(&self) -> Pos {
+        Pos::new(self.col, -self.row)
+    }
+
+    // Rotate 90 degrees counterclockwise about the origin.
+    pub fn rotate_left(&self) -> Pos {
+        Pos::new(-self.col, self.row)
+    }
+
+    // Mirror image across the horizontal line at the given row.
+    pub fn reflect_across_row(&self, row: isize) -> Pos {
+        Pos::new(2*row - self.row, self.col)
+    }
+
+    // Mirror image across the vertical line at the given column.
+    pub fn reflect_across_col(&self, col: isize) -> Pos {
+        Pos::new(self.row, 2*col - self.col)
+    }
+
+    pub fn neighbor8(&self, dir: Dir8) -> Pos {
+        let (row, col) = dir.offset();
+        Pos::new(self.row + row, self.col + col)
+    }
+
+    // The four orthogonally adjacent positions.
+    pub fn neighbors4(&self) -> impl Iterator<Item=Pos> {
+        let pos = *self;
+        Dir::iter().map(move |&dir| pos.neighbor(dir))
+    }
+
+    // The eight adjacent positions, including diagonals.
+    pub fn neighbors8(&self) -> impl Iterator<Item=Pos> {
+        let pos = *self;
+        Dir8::iter().map(move |&dir| pos.neighbor8(dir))
+    }
+}
+
+impl Add for Pos {
+    type Output = Pos;
+
+    fn add(self, other: Pos) -> Pos {
+        Pos::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl AddAssign for Pos {
+    fn add_assign(&mut self, other: Pos) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Pos {
+    type Output = Pos;
+
+    fn sub(self, other: Pos) -> Pos {
+        Pos::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl SubAssign for Pos {
+    fn sub_assign(&mut self, other: Pos) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Pos {
+    type Output = Pos;
+
+    fn neg(self) -> Pos {
+        Pos::new(-self.row, -self.col)
+    }
+}
+
+impl Mul<isize> for Pos {
+    type Output = Pos;
+
+    fn mul(self, scale: isize) -> Pos {
+        Pos::new(self.row * scale, self.col * scale)
+    }
+}
+
+/* A grid with no fixed edges, where every position holds a default value until
+ * written. Implemented by InfiniteGrid (dense, good for compact areas near the
+ * origin) and SparseGrid (hashed, good for scattered or far-flung writes), so
+ * a solver can swap one for the other by changing only the type. */
+pub trait UnboundedGrid<T: Clone+PartialEq>: Index<Pos, Output=T> + IndexMut<Pos> {
+    fn with_default(default: T) -> Self where Self: Sized;
+
+    // The smallest rectangle, as inclusive (top left, bottom right) corners,
+    // containing every position that has been written. None if nothing has
+    // been written yet. Any mutable access counts as a write, even one that
+    // leaves or sets the default.
+    fn bounds(&self) -> Option<(Pos, Pos)>;
+
+    // Every position whose value differs from the default, in no particular
+    // order.
+    fn iter_non_default<'a>(&'a self) -> impl Iterator<Item=(Pos, &'a T)>
+        where T: 'a;
+
+    // Count the non-default positions whose value satisfies pred.
+    fn count_where<F: Fn(&T) -> bool>(&self, pred: F) -> usize {
+        self.iter_non_default().filter(|&(_pos, v)| pred(v)).count()
+    }
+
+    // Every position within bounds() with its value, row by row, including
+    // positions that were never written.
+    fn iter_cropped<'a>(&'a self) -> impl Iterator<Item=(Pos, &'a T)>
+        where T: 'a
+    {
+        let (top_left, bottom_right) = self.bounds()
+                                           .unwrap_or((Pos::new(0, 0),
+                                                       Pos::new(-1, -1)));
+        (top_left.row..=bottom_right.row)
+            .flat_map(move |row| (top_left.col..=bottom_right.col)
+                                     .map(move |col| Pos::new(row, col)))
+            .map(move |pos| (pos, &self[pos]))
+    }
+
+    // The smallest rectangle containing every non-default position, or None
+    // if there are none. Unlike bounds(), this looks at every stored value.
+    fn non_default_bounds(&self) -> Option<(Pos, Pos)> {
+        self.iter_non_default()
+            .fold(None, |bounds, (pos, _v)| extend_bounds(bounds, pos))
+    }
+
+    // Output a finite, cropped grid in the form of a Vec of Vec. Crops the
+    // grid by ignoring any values that are still at the default. Contents are
+    // cloned. The result may actually be an empty Vec if there are no
+    // non-default values.
+    fn crop(&self) -> Vec<Vec<T>> {
+        let (top_left, bottom_right) = match self.non_default_bounds() {
+            Some(bounds) => bounds,
+            None => return Vec::new(),
+        };
+        (top_left.row..=bottom_right.row)
+            .map(|row| (top_left.col..=bottom_right.col)
+                           .map(|col| self[Pos::new(row, col)].clone())
+                           .collect())
+            .collect()
+    }
+}
+
+// Grow an inclusive bounding rectangle to include pos.
+pub(crate) fn extend_bounds(bounds: Option<(Pos, Pos)>, pos: Pos)
+        -> Option<(Pos, Pos)>
+{
+    match bounds {
+        None => Some((pos, pos)),
+        Some((top_left, bottom_right)) => Some((
+            Pos::new(top_left.row.min(pos.row), top_left.col.min(pos.col)),
+            Pos::new(bottom_right.row.max(pos.row),
+                     bottom_right.col.max(pos.col)),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dir, Dir8, Pos};
+
+    #[test]
+    fn dir8_turns_by_45_degrees() {
+        assert_eq!(Dir8::UpRight, Dir8::Up.turn_right());
+        assert_eq!(Dir8::UpLeft, Dir8::Up.turn_left());
+        assert_eq!(Dir8::DownLeft, Dir8::UpRight.reverse());
+        for &dir in Dir8::iter() {
+            assert_eq!(dir, dir.turn_right().turn_left());
+        }
+        assert_eq!(Dir8::Left, Dir8::from(Dir::Left));
+    }
+
+    #[test]
+    fn neighbors() {
+        let pos = pos!(3, -2);
+        assert_eq!(4, pos.neighbors4().count());
+        assert_eq!(8, pos.neighbors8().count());
+        assert!(pos.neighbors4().all(|n| n.manhattan_distance(&pos) == 1));
+        assert!(pos.neighbors8().all(|n| n.chebyshev_distance(&pos) == 1));
+        assert_eq!(pos!(2, -1), pos.neighbor8(Dir8::UpRight));
+    }
+
+    #[test]
+    fn chebyshev_distance() {
+        assert_eq!(5, pos!(0, 0).chebyshev_distance(&pos!(-5, 3)));
+        assert_eq!(8, pos!(0, 0).manhattan_distance(&pos!(-5, 3)));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(pos!(4, -1), pos!(1, 2) + pos!(3, -3));
+        assert_eq!(pos!(-2, 5), pos!(1, 2) - pos!(3, -3));
+        assert_eq!(pos!(-1, -2), -pos!(1, 2));
+        assert_eq!(pos!(3, 6), pos!(1, 2) * 3);
+        assert_eq!(pos!(1, -1), pos!(7, -3).signum());
+    }
+
+    #[test]
+    fn step_toward() {
+        let mut pos = pos!(0, 0);
+        let target = pos!(2, -3);
+        let mut steps = 0;
+        while pos != target {
+            pos = pos.step_toward(&target);
+            steps += 1;
+        }
+        assert_eq!(3, steps);
+        assert_eq!(target, target.step_toward(&target));
+    }
+
+    #[test]
+    fn rotate_and_reflect() {
+        let up = pos!(-1, 0);
+        assert_eq!(pos!(0, 1), up.rotate_right());
+        assert_eq!(pos!(0, -1), up.rotate_left());
+        assert_eq!(up, up.rotate_right().rotate_right().rotate_right()
+                         .rotate_right());
+        assert_eq!(pos!(8, 3), pos!(2, 3).reflect_across_row(5));
+        assert_eq!(pos!(2, 1), pos!(2, 13).reflect_across_col(7));
+    }
 }
@@ -1,4 +1,4 @@
-use super::grid::{Pos};
+use super::grid::{Pos, UnboundedGrid, extend_bounds};
 use std::ops::{Index,IndexMut};
 
 /* An infinite 2-dimensional grid where every position has default value until
@@ -6,85 +6,63 @@ use std::ops::{Index,IndexMut};
 #[derive(Clone)]
 pub struct InfiniteGrid<T: Clone+PartialEq> {
     rows: Vec<Vec<T>>,
-    default: T
+    default: T,
+    bounds: Option<(Pos, Pos)>,
 }
 
 impl<T: Clone+PartialEq> InfiniteGrid<T> {
     pub fn new(default: T) -> InfiniteGrid<T> {
         InfiniteGrid {
             rows: Vec::new(),
-            default: default
+            default,
+            bounds: None,
         }
     }
 
-    // Output a finite, cropped grid in the form of a Vec of Vec. Crops the
-    // grid by ignoring any values that are still at the default. Contents are
-    // cloned. The result may actually be an empty Vec if there are no
-    // non-default values.
-    pub fn crop(&self) -> Vec<Vec<T>> {
-        // I'm sure this could be prettier >_>
-
-        // Find bounds
-        let mut left = None;
-        let mut top = None;
-        let mut right = None;
-        let mut bottom = None;
-        for y_index in 0..self.rows.len() {
-            let row = self.rows.get(y_index).unwrap();
-            let mut first_pos = None;
-            let mut last_pos = None;
-            for x_index in 0..row.len() {
-                if row[x_index] != self.default {
-                    let x_pos = Self::index_to_pos(x_index);
-                    if first_pos.is_none() || x_pos < first_pos.unwrap() {
-                        first_pos = Some(x_pos);
-                    }
-                    if last_pos.is_none() || x_pos > last_pos.unwrap() {
-                        last_pos = Some(x_pos);
-                    }
-                }
-            }
-            if first_pos.is_some() {
-                let y_pos = Self::index_to_pos(y_index);
-                if top.is_none() || y_pos < top.unwrap() {
-                    top = Some(y_pos);
-                }
-                if bottom.is_none() || y_pos > bottom.unwrap() {
-                    bottom = Some(y_pos);
-                }
-                if left.is_none() || first_pos.unwrap() < left.unwrap() {
-                    left = first_pos;
-                }
-                if right.is_none() || last_pos.unwrap() > right.unwrap() {
-                    right = last_pos;
-                }
-            }
-        }
+    // Every stored position with its value, in storage order (not sorted).
+    // Stored positions may still hold the default; positions never stored
+    // are skipped.
+    pub fn iter(&self) -> impl Iterator<Item=(Pos, &T)> {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row_index, row)| {
+                row.iter()
+                   .enumerate()
+                   .map(move |(col_index, v)| {
+                       (Pos::new(Self::index_to_pos(row_index),
+                                 Self::index_to_pos(col_index)), v)
+                   })
+            })
+    }
 
-        // Create cropped grid
-        let mut result: Vec<Vec<T>> = Vec::new();
-        if left.is_some() {
-            let left = left.unwrap();
-            let top = top.unwrap();
-            let right = right.unwrap();
-            let bottom = bottom.unwrap();
-            for y_pos in top..=bottom {
-                let y_index = Self::pos_to_index(y_pos);
-                let orig_row = self.rows.get(y_index).unwrap();
-                let mut row: Vec<T> = Vec::new();
-                for x_pos in left..=right {
-                    // Not all rows are the same width internally
-                    let x_index = Self::pos_to_index(x_pos);
-                    if x_index >= orig_row.len() {
-                        row.push(self.default.clone());
-                    } else {
-                        row.push(orig_row.get(x_index).unwrap().clone());
-                    }
-                }
-                result.push(row);
-            }
+    // Mutable access to every stored value, in storage order. Like any
+    // mutable access this counts as a write, so bounds() grows to cover
+    // every stored position.
+    pub fn values_mut(&mut self) -> impl Iterator<Item=&mut T> {
+        self.bounds = self.stored_bounds();
+        self.rows.iter_mut().flat_map(|row| row.iter_mut())
+    }
+
+    // A new grid with f applied to every stored value and to the default.
+    // Every stored position counts as written in the new grid, as it would
+    // after values_mut().
+    pub fn map<U: Clone+PartialEq, F: Fn(&T) -> U>(&self, f: F)
+            -> InfiniteGrid<U>
+    {
+        InfiniteGrid {
+            rows: self.rows
+                      .iter()
+                      .map(|row| row.iter().map(&f).collect())
+                      .collect(),
+            default: f(&self.default),
+            bounds: self.stored_bounds(),
         }
-        result
+    }
+
+    // The smallest rectangle containing every stored position.
+    fn stored_bounds(&self) -> Option<(Pos, Pos)> {
+        self.iter().fold(None, |bounds, (pos, _v)| extend_bounds(bounds, pos))
     }
 
     // Ensure the underlying vectors have min_rows and min_cols capacity.
@@ -124,11 +102,27 @@ impl<T: Clone+PartialEq> InfiniteGrid<T> {
     }
 }
 
+impl<T: Clone+PartialEq> UnboundedGrid<T> for InfiniteGrid<T> {
+    fn with_default(default: T) -> Self {
+        Self::new(default)
+    }
+
+    fn bounds(&self) -> Option<(Pos, Pos)> {
+        self.bounds
+    }
+
+    fn iter_non_default<'a>(&'a self) -> impl Iterator<Item=(Pos, &'a T)>
+        where T: 'a
+    {
+        self.iter().filter(move |&(_pos, v)| *v != self.default)
+    }
+}
+
 // Index into the grid at the position (row, col).
 impl<T: Clone+PartialEq> Index<Pos> for InfiniteGrid<T> {
     type Output = T;
 
-    fn index<'a>(&'a self, pos: Pos) -> &'a T {
+    fn index(&self, pos: Pos) -> &T {
         let row_index = Self::pos_to_index(pos.row);
         let col_index = Self::pos_to_index(pos.col);
         if self.rows.len() <= row_index ||
@@ -141,10 +135,11 @@ impl<T: Clone+PartialEq> Index<Pos> for InfiniteGrid<T> {
 
 // Write into the grid at the position (row, col).
 impl<T: Clone+PartialEq> IndexMut<Pos> for InfiniteGrid<T> {
-    fn index_mut<'a>(&'a mut self, pos: Pos) -> &'a mut T {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
         let row_index = Self::pos_to_index(pos.row);
         let col_index = Self::pos_to_index(pos.col);
         self.ensure_capacity(row_index+1, col_index+1);
+        self.bounds = extend_bounds(self.bounds, pos);
         &mut self.rows[row_index][col_index]
     }
 }
@@ -152,7 +147,7 @@ impl<T: Clone+PartialEq> IndexMut<Pos> for InfiniteGrid<T> {
 #[cfg(test)]
 mod tests {
     use super::InfiniteGrid;
-    use super::super::grid::Pos;
+    use super::super::grid::{Pos, UnboundedGrid};
     use crate::pos;
 
     #[test]
@@ -187,4 +182,77 @@ mod tests {
         grid[pos!(0, 0)] = 'z';
         assert!(grid[pos!(0, 0)] == 'z');
     }
+
+    #[test]
+    fn tracks_bounds_of_writes() {
+        let mut grid: InfiniteGrid<u8> = InfiniteGrid::new(0);
+        assert_eq!(None, grid.bounds());
+        assert_eq!(0, grid.iter_cropped().count());
+        assert!(grid.crop().is_empty());
+
+        grid[pos!(2, -1)] = 1;
+        grid[pos!(-1, 1)] = 2;
+        assert_eq!(Some((pos!(-1, -1), pos!(2, 1))), grid.bounds());
+
+        let cells: Vec<(Pos, &u8)> = grid.iter_cropped().collect();
+        assert_eq!(12, cells.len());
+        assert_eq!((pos!(-1, -1), &0), cells[0]);
+        assert_eq!((pos!(-1, 1), &2), cells[2]);
+        assert_eq!((pos!(2, -1), &1), cells[9]);
+        assert_eq!(vec![vec![0, 0, 2], vec![0; 3], vec![0; 3], vec![1, 0, 0]],
+                   grid.crop());
+    }
+
+    #[test]
+    fn crop_ignores_default_values() {
+        let mut grid: InfiniteGrid<u8> = InfiniteGrid::new(0);
+        grid[pos!(0, 0)] = 1;
+        grid[pos!(5, -5)] = 0;
+        grid[pos!(0, 1)] += 0;
+        assert_eq!(Some((pos!(0, -5), pos!(5, 1))), grid.bounds());
+        assert_eq!(Some((pos!(0, 0), pos!(0, 0))), grid.non_default_bounds());
+        assert_eq!(vec![vec![1]], grid.crop());
+    }
+
+    #[test]
+    fn iterates_and_counts_with_negative_coordinates() {
+        let mut grid: InfiniteGrid<u8> = InfiniteGrid::new(0);
+        grid[pos!(-3, 2)] = 2;
+        grid[pos!(1, -1)] = 1;
+        grid[pos!(0, 0)] += 3;
+
+        let mut set: Vec<(Pos, u8)> = grid.iter_non_default()
+                                          .map(|(pos, &v)| (pos, v))
+                                          .collect();
+        set.sort_by_key(|&(pos, _v)| (pos.row, pos.col));
+        assert_eq!(vec![(pos!(-3, 2), 2), (pos!(0, 0), 3), (pos!(1, -1), 1)],
+                   set);
+        assert!(grid.iter().all(|(pos, &v)| grid[pos] == v));
+        assert_eq!(2, grid.count_where(|&v| v > 1));
+
+        for v in grid.values_mut() {
+            *v *= 2;
+        }
+        let doubled = grid.map(|&v| v > 2);
+        assert!(!doubled[pos!(100, 100)]);
+        assert!(doubled[pos!(-3, 2)]);
+        assert!(!doubled[pos!(1, -1)]);
+        assert_eq!(grid.bounds(), doubled.bounds());
+    }
+
+    #[test]
+    fn bulk_updates_extend_bounds() {
+        let mut grid: InfiniteGrid<u8> = InfiniteGrid::new(0);
+        grid[pos!(3, 3)] = 1;
+        for v in grid.values_mut() {
+            *v += 1;
+        }
+        let doubled = grid.map(|&v| v * 2);
+        for grid in [&grid, &doubled] {
+            let non_default = grid.iter_non_default().count();
+            assert!(non_default > 1);
+            assert_eq!(non_default, grid.iter_cropped().count());
+            assert_eq!(grid.bounds(), grid.non_default_bounds());
+        }
+    }
 }
@@ -1,20 +1,185 @@
-use anyhow::Error;
+use anyhow::{Error, format_err};
+use std::fmt;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Read};
 use std::str::FromStr;
 
+// Path that means "read from stdin" rather than a file.
+pub const STDIN_PATH: &str = "-";
+
+// Read the whole input at path into memory, or stdin if the path is "-".
+pub fn read_input(path: &str) -> Result<String, Error> {
+    let mut text = String::new();
+    if path == STDIN_PATH {
+        io::stdin().read_to_string(&mut text)?;
+    } else {
+        File::open(path).map_err(|e| format_err!("{}: {}", path, e))?
+                        .read_to_string(&mut text)?;
+    }
+    Ok(text)
+}
+
+/* A problem with the input, pointing at where it was found, displayed as:
+ *
+ *   input/day05.txt:3:10: invalid digit found in string
+ *     0,9 -> x,9
+ *            ^
+ *
+ * Parsers build these from a Line; the runner fills in the path afterward
+ * with attach_path, since parsers only see a reader. */
+#[derive(Debug)]
+pub struct ParseError {
+    pub path: Option<String>,
+    // 1-based
+    pub line: usize,
+    // 1-based, in characters
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}:", path)?,
+            None => write!(f, "line ")?,
+        }
+        writeln!(f, "{}:{}: {}", self.line, self.column, self.message)?;
+        writeln!(f, "  {}", self.text)?;
+        write!(f, "  {}^", " ".repeat(self.column.saturating_sub(1)))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// If error is a ParseError, record the path of the input it came from.
+pub fn attach_path(mut error: Error, path: &str) -> Error {
+    if let Some(parse_error) = error.downcast_mut::<ParseError>() {
+        parse_error.path = Some(if path == STDIN_PATH {
+                                    "<stdin>".to_string()
+                                } else {
+                                    path.to_string()
+                                });
+    }
+    error
+}
+
+// One line of input and its 1-based line number, for reporting ParseErrors.
+#[derive(Clone,Copy,Debug)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    // An error about the line as a whole, pointing at its start.
+    pub fn error<M: ToString>(&self, message: M) -> ParseError {
+        self.error_at(1, message)
+    }
+
+    // An error pointing at the given (1-based) column.
+    pub fn error_at<M: ToString>(&self, column: usize, message: M)
+            -> ParseError
+    {
+        ParseError {
+            path: None,
+            line: self.number,
+            column,
+            text: self.text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    // An error pointing at part, which should be a slice of this line's text.
+    pub fn error_in<M: ToString>(&self, part: &str, message: M) -> ParseError {
+        self.error_at(self.column_of(part), message)
+    }
+
+    // Parse part, a slice of this line's text, pointing at it on failure.
+    pub fn parse<T>(&self, part: &str) -> Result<T, ParseError>
+        where T: FromStr, <T as FromStr>::Err: fmt::Display
+    {
+        part.parse::<T>()
+            .map_err(|e| self.error_in(part, format!("{}: {:?}", e, part)))
+    }
+
+    // Parse every sep-separated field of the line.
+    pub fn parse_list<T>(&self, sep: &str) -> Result<Vec<T>, ParseError>
+        where T: FromStr, <T as FromStr>::Err: fmt::Display
+    {
+        self.text
+            .split(sep)
+            .map(|field| self.parse::<T>(field))
+            .collect()
+    }
+
+    // The 1-based column where part starts, or 1 if part is not a slice of
+    // this line.
+    fn column_of(&self, part: &str) -> usize {
+        let start = self.text.as_ptr() as usize;
+        let offset = (part.as_ptr() as usize).wrapping_sub(start);
+        if offset <= self.text.len() && self.text.is_char_boundary(offset) {
+            self.text[..offset].chars().count() + 1
+        } else {
+            1
+        }
+    }
+}
+
+// Number the lines of the input, starting at 1.
+pub fn numbered<S: AsRef<str>>(lines: &[S]) -> impl Iterator<Item=Line<'_>> {
+    lines.iter()
+         .enumerate()
+         .map(|(i, text)| Line { number: i + 1, text: text.as_ref() })
+}
+
+pub fn read_numbers<T, R>(reader: R) -> Result<Vec<T>, Error>
+                       where T: FromStr,
+                             R: BufRead,
+           <T as FromStr>::Err: fmt::Display {
+    let lines = read_lines(reader)?;
+    numbered(&lines).map(|line| Ok(line.parse::<T>(line.text)?))
+                    .collect::<Result<Vec<T>, Error>>()
+}
+
+pub fn read_lines<R: BufRead>(reader: R) -> Result<Vec<String>, Error> {
+    reader.lines()
+          .map(|line| Ok(line?))
+          .collect::<Result<Vec<String>, _>>()
+}
+
+pub fn read_to_string<R: Read>(mut reader: R) -> Result<String, Error> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    Ok(text)
+}
+
 pub fn read_numbers_from_file<T>(path: &str) -> Result<Vec<T>, Error>
                        where T: FromStr,
-           <T as FromStr>::Err: 'static + Send + Sync + std::error::Error {
-    BufReader::new(File::open(path)?)
-              .lines()
-              .map(|line| Ok(line?.parse::<T>()?))
-              .collect::<Result<Vec<T>, Error>>()
+           <T as FromStr>::Err: fmt::Display {
+    read_numbers(BufReader::new(File::open(path)?))
+        .map_err(|e| attach_path(e, path))
 }
 
 pub fn read_lines_from_file(path: &str) -> Result<Vec<String>, Error> {
-    BufReader::new(File::open(path)?)
-              .lines()
-              .map(|line| Ok(line?))
-              .collect::<Result<Vec<String>, _>>()
+    read_lines(BufReader::new(File::open(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseError, attach_path, numbered};
+
+    #[test]
+    fn points_at_the_offending_field() {
+        let lines = vec!["1,2,3", "4,x,6"];
+        let line = numbered(&lines).nth(1).unwrap();
+        let error = line.parse_list::<u8>(",").unwrap_err();
+        assert_eq!((2, 3), (error.line, error.column));
+        let error = attach_path(error.into(), "input/day99.txt");
+        assert_eq!("input/day99.txt:2:3: invalid digit found in string: \"x\"\n\
+                    \x20 4,x,6\n\
+                    \x20   ^",
+                   error.to_string());
+        assert!(error.downcast_ref::<ParseError>().is_some());
+    }
 }
@@ -1,3 +1,10 @@
 pub mod io;
+pub mod dijkstra;
 pub mod grid;
+pub mod ocr;
+pub mod point;
+pub mod search;
+mod bounded_grid;
 mod infinite_grid;
+mod render;
+mod sparse_grid;
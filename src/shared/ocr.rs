@@ -0,0 +1,147 @@
+// Recognition of the block capitals some puzzles draw as their answer. Each
+// letter is 4 cells wide and 6 tall, and letters are spaced 5 cells apart.
+
+use super::grid::{Grid, Pos};
+use anyhow::{Error, format_err};
+
+const LETTER_WIDTH: usize = 4;
+const LETTER_HEIGHT: usize = 6;
+const LETTER_SPACING: usize = 5;
+
+static LETTERS: [(char, [&str; LETTER_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+// Read the letters drawn by the set cells of grid, which must be exactly one
+// letter tall. Empty columns after the last letter may be omitted.
+pub fn recognize(grid: &Grid<bool>) -> Result<String, Error> {
+    if grid.height() != LETTER_HEIGHT {
+        return Err(format_err!("Letters must be {} rows tall, got {}",
+                               LETTER_HEIGHT, grid.height()));
+    }
+    (0..grid.width()).step_by(LETTER_SPACING)
+                     .map(|left| recognize_letter(grid, left))
+                     .collect()
+}
+
+// Read the letters drawn by points, ignoring any empty rows above them and
+// any empty letter-wide cells to their left. Letters must line up with the
+// cells that start at column 0, since some (like I) leave their first column
+// empty.
+pub fn recognize_points<'a, I>(points: I) -> Result<String, Error>
+    where I: IntoIterator<Item=&'a Pos>
+{
+    let points: Vec<Pos> = points.into_iter().cloned().collect();
+    let spacing = LETTER_SPACING as isize;
+    let top = points.iter().map(|p| p.row).min().unwrap_or(0);
+    let left = points.iter()
+                     .map(|p| p.col.div_euclid(spacing) * spacing)
+                     .min()
+                     .unwrap_or(0);
+    let bottom = points.iter().map(|p| p.row).max().unwrap_or(-1);
+    let right = points.iter().map(|p| p.col).max().unwrap_or(-1);
+    let mut grid = Grid::new((right - left + 1) as usize,
+                             (bottom - top + 1) as usize,
+                             false);
+    for point in points {
+        grid[Pos::new(point.row - top, point.col - left)] = true;
+    }
+    recognize(&grid)
+}
+
+fn recognize_letter(grid: &Grid<bool>, left: usize) -> Result<char, Error> {
+    let cell = |row: usize, col: usize| {
+        *grid.get(Pos::new(row as isize, (left + col) as isize))
+             .unwrap_or(&false)
+    };
+    LETTERS.iter()
+           .find(|(_c, pattern)| {
+               pattern.iter().enumerate().all(|(row, line)| {
+                   line.chars()
+                       .enumerate()
+                       .all(|(col, ch)| (ch == '#') == cell(row, col))
+               })
+           })
+           .map(|&(c, _pattern)| c)
+           .ok_or_else(|| {
+               let glyph: Vec<String> = (0..LETTER_HEIGHT)
+                   .map(|row| (0..LETTER_WIDTH)
+                                  .map(|col| if cell(row, col) { '#' } else { '.' })
+                                  .collect())
+                   .collect();
+               format_err!("Unrecognized letter at column {}:\n{}",
+                           left, glyph.join("\n"))
+           })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recognize, recognize_points};
+    use super::super::grid::{Grid, Pos};
+    use crate::pos;
+
+    fn parse(rows: &[&str]) -> Grid<bool> {
+        Grid::from_rows(rows.iter()
+                            .map(|row| row.chars().map(|c| c == '#').collect())
+                            .collect())
+             .unwrap()
+    }
+
+    #[test]
+    fn reads_letters() {
+        let grid = parse(&["#..#.####.####",
+                           "#..#.#....#...",
+                           "####.###..###.",
+                           "#..#.#....#...",
+                           "#..#.#....#...",
+                           "#..#.#....####"]);
+        assert_eq!("HFE", recognize(&grid).unwrap());
+    }
+
+    #[test]
+    fn reads_offset_points() {
+        // An L drawn away from the origin, one letter cell to the left
+        let mut points: Vec<Pos> = (0..6).map(|r| pos!(r + 10, -5)).collect();
+        points.extend((0..4).map(|c| pos!(15, c - 5)));
+        assert_eq!("L", recognize_points(&points).unwrap());
+    }
+
+    #[test]
+    fn reads_points_starting_with_blank_column() {
+        // I leaves column 0 empty, which must not shift the letters after it
+        let grid = parse(&[".###.#...",
+                           "..#..#...",
+                           "..#..#...",
+                           "..#..#...",
+                           "..#..#...",
+                           ".###.####"]);
+        let points: Vec<Pos> = grid.iter()
+                                   .filter(|&(_pos, &set)| set)
+                                   .map(|(pos, _set)| pos)
+                                   .collect();
+        assert_eq!("IL", recognize_points(&points).unwrap());
+    }
+
+    #[test]
+    fn rejects_unknown_shapes() {
+        assert!(recognize(&parse(&["####"; 6])).is_err());
+        assert!(recognize(&parse(&["#..#"; 5])).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+// Points in N-dimensional integer space, for puzzles with more than two
+// axes. Point<2> converts to and from grid::Pos (as [row, col]).
+
+use super::grid::Pos;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+#[derive(Clone,Copy,Debug,Eq,Hash,Ord,PartialEq,PartialOrd)]
+pub struct Point<const N: usize> {
+    pub coords: [isize; N],
+}
+
+pub type Point3 = Point<3>;
+pub type Point4 = Point<4>;
+
+impl<const N: usize> Point<N> {
+    pub fn new(coords: [isize; N]) -> Self {
+        Point { coords }
+    }
+
+    pub fn origin() -> Self {
+        Point::new([0; N])
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        self.coords.iter()
+                   .zip(other.coords.iter())
+                   .map(|(a, b)| (a - b).unsigned_abs())
+                   .sum()
+    }
+
+    // Number of king's moves between the points.
+    pub fn chebyshev_distance(&self, other: &Self) -> usize {
+        self.coords.iter()
+                   .zip(other.coords.iter())
+                   .map(|(a, b)| (a - b).unsigned_abs())
+                   .max()
+                   .unwrap_or(0)
+    }
+
+    // Each coordinate's sign: -1, 0 or 1.
+    pub fn signum(&self) -> Self {
+        Point::new(self.coords.map(|c| c.signum()))
+    }
+
+    // One step (possibly diagonal) from here toward target, or staying put
+    // if already there.
+    pub fn step_toward(&self, target: &Self) -> Self {
+        *self + (*target - *self).signum()
+    }
+
+    // The point moved by delta along a single axis.
+    pub fn neighbor(&self, axis: usize, delta: isize) -> Self {
+        let mut result = *self;
+        result.coords[axis] += delta;
+        result
+    }
+
+    // The 2N points that differ by one along exactly one axis.
+    pub fn orthogonal_neighbors(&self) -> impl Iterator<Item=Self> {
+        let point = *self;
+        (0..N).flat_map(move |axis| [point.neighbor(axis, -1),
+                                     point.neighbor(axis, 1)])
+    }
+
+    // All 3^N - 1 adjacent points, including diagonals.
+    pub fn neighbors(&self) -> impl Iterator<Item=Self> {
+        let point = *self;
+        (0..3usize.pow(N as u32))
+            .map(move |i| {
+                let mut offset = [0; N];
+                let mut rest = i;
+                for c in offset.iter_mut() {
+                    *c = (rest % 3) as isize - 1;
+                    rest /= 3;
+                }
+                point + Point::new(offset)
+            })
+            .filter(move |&n| n != point)
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = isize;
+
+    fn index(&self, axis: usize) -> &isize {
+        &self.coords[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut isize {
+        &mut self.coords[axis]
+    }
+}
+
+impl<const N: usize> Add for Point<N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut result = self;
+        result += other;
+        result
+    }
+}
+
+impl<const N: usize> AddAssign for Point<N> {
+    fn add_assign(&mut self, other: Self) {
+        for (a, b) in self.coords.iter_mut().zip(other.coords.iter()) {
+            *a += b;
+        }
+    }
+}
+
+impl<const N: usize> Sub for Point<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        let mut result = self;
+        result -= other;
+        result
+    }
+}
+
+impl<const N: usize> SubAssign for Point<N> {
+    fn sub_assign(&mut self, other: Self) {
+        for (a, b) in self.coords.iter_mut().zip(other.coords.iter()) {
+            *a -= b;
+        }
+    }
+}
+
+impl<const N: usize> Neg for Point<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point::new(self.coords.map(|c| -c))
+    }
+}
+
+impl<const N: usize> Mul<isize> for Point<N> {
+    type Output = Self;
+
+    fn mul(self, scale: isize) -> Self {
+        Point::new(self.coords.map(|c| c * scale))
+    }
+}
+
+impl From<Pos> for Point<2> {
+    fn from(pos: Pos) -> Self {
+        Point::new([pos.row, pos.col])
+    }
+}
+
+impl From<Point<2>> for Pos {
+    fn from(point: Point<2>) -> Self {
+        Pos::new(point[0], point[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Point, Point3, Point4};
+    use crate::pos;
+    use crate::shared::grid::Pos;
+
+    #[test]
+    fn distances() {
+        let a = Point3::new([1, -2, 3]);
+        let b = Point3::new([-4, 0, 3]);
+        assert_eq!(7, a.manhattan_distance(&b));
+        assert_eq!(5, a.chebyshev_distance(&b));
+    }
+
+    #[test]
+    fn neighbor_counts() {
+        let p = Point4::new([0, 5, -5, 1]);
+        assert_eq!(8, p.orthogonal_neighbors().count());
+        assert_eq!(80, p.neighbors().count());
+        assert!(p.neighbors().all(|n| p.chebyshev_distance(&n) == 1));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Point3::new([1, 2, 3]);
+        assert_eq!(Point3::new([2, 4, 6]), a + a);
+        assert_eq!(Point3::origin(), a - a);
+        assert_eq!(Point3::new([-3, -6, -9]), -a * 3);
+        assert_eq!(Point3::new([1, 1, 1]), Point3::origin().step_toward(&a));
+    }
+
+    #[test]
+    fn converts_to_and_from_pos() {
+        let pos = pos!(3, -7);
+        let point = Point::from(pos);
+        assert_eq!(Point::new([3, -7]), point);
+        assert_eq!(pos, Pos::from(point));
+        assert_eq!(pos.manhattan_distance(&Pos::origin()),
+                   point.manhattan_distance(&Point::origin()));
+    }
+}
@@ -0,0 +1,219 @@
+use super::grid::{Grid, InfiniteGrid, Pos, SparseGrid, UnboundedGrid};
+use std::fmt;
+
+/* A grid that can be drawn as text: a rectangle of positions (inclusive
+ * corners, possibly negative) and the cell at each of them. */
+pub trait Renderable {
+    type Cell;
+
+    // The (top left, bottom right) corners to draw, or None if empty.
+    fn extent(&self) -> Option<(Pos, Pos)>;
+
+    fn cell(&self, pos: Pos) -> &Self::Cell;
+
+    // Draw each cell with its default character.
+    fn render(&self) -> Rendering<'_, Self>
+        where Self::Cell: CellChar
+    {
+        Rendering::new(self, |cell: &Self::Cell| cell.to_char())
+    }
+
+    // Draw each cell with the character chosen by to_char.
+    fn render_with<'a, F>(&'a self, to_char: F) -> Rendering<'a, Self>
+        where F: Fn(&Self::Cell) -> char + 'a
+    {
+        Rendering::new(self, to_char)
+    }
+}
+
+// The character a cell is drawn with by default.
+pub trait CellChar {
+    fn to_char(&self) -> char;
+}
+
+impl CellChar for bool {
+    fn to_char(&self) -> char {
+        if *self { '#' } else { '.' }
+    }
+}
+
+impl CellChar for char {
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+// Digits draw as themselves; anything above 9 draws as '+'.
+macro_rules! cell_char_from_digit {
+    ($($t:ty),*) => {
+        $(
+            impl CellChar for $t {
+                fn to_char(&self) -> char {
+                    u32::try_from(*self).ok()
+                                        .and_then(|d| char::from_digit(d, 10))
+                                        .unwrap_or('+')
+                }
+            }
+        )*
+    }
+}
+
+cell_char_from_digit!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+type CellToChar<'a, C> = Box<dyn Fn(&C) -> char + 'a>;
+
+/* A grid drawn as text, one line per row. Use lines() for an Answer::Grid, or
+ * format it with {} to print it. */
+pub struct Rendering<'a, G: Renderable + ?Sized> {
+    grid: &'a G,
+    to_char: CellToChar<'a, G::Cell>,
+    labels: bool,
+}
+
+impl<'a, G: Renderable + ?Sized> Rendering<'a, G> {
+    pub fn new<F>(grid: &'a G, to_char: F) -> Rendering<'a, G>
+        where F: Fn(&G::Cell) -> char + 'a
+    {
+        Rendering {
+            grid,
+            to_char: Box::new(to_char),
+            labels: false,
+        }
+    }
+
+    // Label rows down the left side, and columns across the top with their
+    // numbers written vertically.
+    pub fn with_labels(mut self) -> Self {
+        self.labels = true;
+        self
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let (top_left, bottom_right) = match self.grid.extent() {
+            Some(extent) => extent,
+            None => return Vec::new(),
+        };
+        let rows = top_left.row..=bottom_right.row;
+        let cols = top_left.col..=bottom_right.col;
+        let mut result = Vec::new();
+
+        let label_width = if self.labels {
+            rows.clone().map(|r| r.to_string().len()).max().unwrap_or(0)
+        } else {
+            0
+        };
+        if self.labels {
+            let col_labels: Vec<String> = cols.clone()
+                                              .map(|c| c.to_string())
+                                              .collect();
+            let height = col_labels.iter().map(|l| l.len()).max().unwrap_or(0);
+            for i in 0..height {
+                let mut line = " ".repeat(label_width + 1);
+                for label in col_labels.iter() {
+                    // Right-align each label so the units line up.
+                    let offset = height - label.len();
+                    line.push(if i < offset {
+                        ' '
+                    } else {
+                        label.as_bytes()[i - offset] as char
+                    });
+                }
+                result.push(line.trim_end().to_string());
+            }
+        }
+
+        for row in rows {
+            let mut line = String::new();
+            if self.labels {
+                line.push_str(&format!("{:>width$} ", row, width=label_width));
+            }
+            for col in cols.clone() {
+                line.push((self.to_char)(self.grid.cell(Pos::new(row, col))));
+            }
+            result.push(line);
+        }
+        result
+    }
+}
+
+impl<'a, G: Renderable + ?Sized> fmt::Display for Rendering<'a, G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in self.lines() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Renderable for Grid<T> {
+    type Cell = T;
+
+    fn extent(&self) -> Option<(Pos, Pos)> {
+        if self.width() == 0 || self.height() == 0 {
+            None
+        } else {
+            Some((Pos::origin(),
+                  Pos::new(self.height() as isize - 1,
+                           self.width() as isize - 1)))
+        }
+    }
+
+    fn cell(&self, pos: Pos) -> &T {
+        &self[pos]
+    }
+}
+
+impl<T: Clone+PartialEq> Renderable for InfiniteGrid<T> {
+    type Cell = T;
+
+    fn extent(&self) -> Option<(Pos, Pos)> {
+        self.bounds()
+    }
+
+    fn cell(&self, pos: Pos) -> &T {
+        &self[pos]
+    }
+}
+
+impl<T: Clone+PartialEq> Renderable for SparseGrid<T> {
+    type Cell = T;
+
+    fn extent(&self) -> Option<(Pos, Pos)> {
+        self.bounds()
+    }
+
+    fn cell(&self, pos: Pos) -> &T {
+        &self[pos]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Renderable;
+    use super::super::grid::{Grid, InfiniteGrid, Pos};
+    use crate::pos;
+
+    #[test]
+    fn renders_digits_and_bools() {
+        let grid: Grid<u8> = Grid::from_digits(&["105", "020"]).unwrap();
+        assert_eq!(vec!["105", "020"], grid.render().lines());
+        let grid = Grid::new(2, 1, true);
+        assert_eq!("##\n", grid.render().to_string());
+        assert_eq!(vec!["yy"],
+                   grid.render_with(|&b| if b { 'y' } else { 'n' }).lines());
+    }
+
+    #[test]
+    fn labels_negative_coordinates() {
+        let mut grid: InfiniteGrid<bool> = InfiniteGrid::new(false);
+        grid[pos!(-1, -2)] = true;
+        grid[pos!(10, 0)] = true;
+        let lines = grid.render().with_labels().lines();
+        assert_eq!(14, lines.len());
+        assert_eq!("   --", lines[0]);
+        assert_eq!("   210", lines[1]);
+        assert_eq!("-1 #..", lines[2]);
+        assert_eq!(" 0 ...", lines[3]);
+        assert_eq!("10 ..#", lines[13]);
+    }
+}
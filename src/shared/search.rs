@@ -0,0 +1,287 @@
+// Generic graph searches. Graphs are given implicitly by a neighbors(node)
+// closure yielding (neighbor, cost) pairs, so nodes can be anything hashable:
+// grid positions, cave names, whole search states. Breadth- and depth-first
+// searches count steps and ignore costs; Dijkstra and A* add up the costs.
+// When nodes can be numbered densely, like grid cells, dense_dijkstra keeps
+// its state in plain Vecs instead and is much faster.
+
+use super::dijkstra::{self, ShortestPaths};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+// What a search found: each reached node's distance from the start and the
+// node it was reached from, and the order nodes were visited in.
+pub struct Search<N> {
+    order: Vec<N>,
+    distances: HashMap<N, u64>,
+    parents: HashMap<N, N>,
+}
+
+impl<N: Clone+Eq+Hash> Search<N> {
+    fn new(start: &N) -> Search<N> {
+        Search {
+            order: Vec::new(),
+            distances: [(start.clone(), 0)].into_iter().collect(),
+            parents: HashMap::new(),
+        }
+    }
+
+    // Nodes in the order the search visited them, starting with the start.
+    pub fn order(&self) -> &[N] {
+        &self.order
+    }
+
+    // Distance from the start (steps, or total cost for Dijkstra and A*), or
+    // None if node wasn't reached.
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.distances.get(node).cloned()
+    }
+
+    pub fn parent(&self, node: &N) -> Option<&N> {
+        self.parents.get(node)
+    }
+
+    // The nodes from the start to node, inclusive, or None if node wasn't
+    // reached.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.distance(node)?;
+        let mut path = vec![node.clone()];
+        let mut current = node;
+        while let Some(parent) = self.parents.get(current) {
+            path.push(parent.clone());
+            current = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// Visit every node reachable from start, nearest (fewest steps) first.
+pub fn bfs<N, F, I>(start: N, mut neighbors: F) -> Search<N>
+    where N: Clone+Eq+Hash,
+          F: FnMut(&N) -> I,
+          I: IntoIterator<Item=(N, u64)>
+{
+    let mut search = Search::new(&start);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = search.distances[&node];
+        for (neighbor, _cost) in neighbors(&node) {
+            if !search.distances.contains_key(&neighbor) {
+                search.distances.insert(neighbor.clone(), distance + 1);
+                search.parents.insert(neighbor.clone(), node.clone());
+                queue.push_back(neighbor);
+            }
+        }
+        search.order.push(node);
+    }
+    search
+}
+
+// Visit every node reachable from start, following each branch as deep as it
+// goes before backtracking. Distances are depths in the resulting tree.
+pub fn dfs<N, F, I>(start: N, mut neighbors: F) -> Search<N>
+    where N: Clone+Eq+Hash,
+          F: FnMut(&N) -> I,
+          I: IntoIterator<Item=(N, u64)>
+{
+    let mut search = Search::new(&start);
+    let mut stack: Vec<(N, Option<N>)> = vec![(start, None)];
+    while let Some((node, parent)) = stack.pop() {
+        if let Some(parent) = parent {
+            if search.distances.contains_key(&node) {
+                // Already reached along another branch
+                continue;
+            }
+            let depth = search.distances[&parent] + 1;
+            search.distances.insert(node.clone(), depth);
+            search.parents.insert(node.clone(), parent);
+        }
+        let mut next: Vec<(N, Option<N>)> =
+            neighbors(&node).into_iter()
+                            .filter(|(n, _)| !search.distances.contains_key(n))
+                            .map(|(n, _cost)| (n, Some(node.clone())))
+                            .collect();
+        // Reversed so the first neighbor is explored first.
+        next.reverse();
+        stack.extend(next);
+        search.order.push(node);
+    }
+    search
+}
+
+// Visit nodes reachable from start in order of least total cost, stopping
+// once goal (if given) is reached. Costs must not be negative.
+pub fn dijkstra<N, F, I>(start: N, goal: Option<&N>, neighbors: F) -> Search<N>
+    where N: Clone+Eq+Hash,
+          F: FnMut(&N) -> I,
+          I: IntoIterator<Item=(N, u64)>
+{
+    best_first(start, goal, neighbors, |_node| 0)
+}
+
+// Dijkstra over nodes numbered 0..node_count, stopping once goal (if given)
+// is reached. Gives the same distances and paths as dijkstra(), without the
+// visit order.
+pub fn dense_dijkstra<F, I>(node_count: usize, start: usize,
+                            goal: Option<usize>, neighbors: F)
+        -> ShortestPaths
+    where F: FnMut(usize) -> I,
+          I: IntoIterator<Item=(usize, u64)>
+{
+    dijkstra::dijkstra(node_count, start, goal, neighbors)
+}
+
+// Find a cheapest path from start to goal, exploring first the nodes that
+// heuristic (an estimate of the remaining cost that must never overestimate
+// it) says are most promising.
+pub fn astar<N, F, I, H>(start: N, goal: &N, neighbors: F, heuristic: H)
+        -> Search<N>
+    where N: Clone+Eq+Hash,
+          F: FnMut(&N) -> I,
+          I: IntoIterator<Item=(N, u64)>,
+          H: Fn(&N) -> u64
+{
+    best_first(start, Some(goal), neighbors, heuristic)
+}
+
+fn best_first<N, F, I, H>(start: N, goal: Option<&N>, mut neighbors: F,
+                          heuristic: H) -> Search<N>
+    where N: Clone+Eq+Hash,
+          F: FnMut(&N) -> I,
+          I: IntoIterator<Item=(N, u64)>,
+          H: Fn(&N) -> u64
+{
+    let mut search = Search::new(&start);
+    let mut visited = HashSet::new();
+    let mut frontier = BinaryHeap::new();
+    frontier.push(Candidate {
+        estimate: heuristic(&start),
+        cost: 0,
+        node: start,
+    });
+
+    while let Some(Candidate { cost, node, .. }) = frontier.pop() {
+        if cost > search.distances[&node] || !visited.insert(node.clone()) {
+            // A stale entry, superseded by a cheaper path found later
+            continue;
+        }
+        search.order.push(node.clone());
+        if Some(&node) == goal {
+            break;
+        }
+        for (neighbor, step) in neighbors(&node) {
+            let candidate = cost + step;
+            let better = match search.distance(&neighbor) {
+                Some(distance) => candidate < distance,
+                None => true,
+            };
+            if better && !visited.contains(&neighbor) {
+                search.distances.insert(neighbor.clone(), candidate);
+                search.parents.insert(neighbor.clone(), node.clone());
+                frontier.push(Candidate {
+                    estimate: candidate + heuristic(&neighbor),
+                    cost: candidate,
+                    node: neighbor,
+                });
+            }
+        }
+    }
+    search
+}
+
+// A node waiting to be visited, ordered so BinaryHeap pops the lowest
+// estimated total cost first.
+struct Candidate<N> {
+    estimate: u64,
+    cost: u64,
+    node: N,
+}
+
+impl<N> PartialEq for Candidate<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<N> Eq for Candidate<N> {}
+
+impl<N> PartialOrd for Candidate<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Candidate<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, dense_dijkstra, dfs, dijkstra};
+    use std::collections::HashMap;
+
+    //   a --1-- b --1-- d
+    //   |               |
+    //   5       e       1
+    //   |               |
+    //   c ------1------ f
+    fn graph() -> HashMap<char, Vec<(char, u64)>> {
+        let edges = [('a', 'b', 1), ('b', 'd', 1), ('a', 'c', 5),
+                     ('d', 'f', 1), ('c', 'f', 1)];
+        let mut graph: HashMap<char, Vec<(char, u64)>> = HashMap::new();
+        for &(from, to, cost) in edges.iter() {
+            graph.entry(from).or_default().push((to, cost));
+            graph.entry(to).or_default().push((from, cost));
+        }
+        graph
+    }
+
+    #[test]
+    fn bfs_counts_steps() {
+        let graph = graph();
+        let search = bfs('a', |n| graph[n].clone());
+        assert_eq!(vec!['a', 'b', 'c', 'd', 'f'], search.order());
+        assert_eq!(Some(2), search.distance(&'f'));
+        assert_eq!(Some(vec!['a', 'c', 'f']), search.path_to(&'f'));
+        assert_eq!(None, search.distance(&'e'));
+    }
+
+    #[test]
+    fn dfs_goes_deep_first() {
+        let graph = graph();
+        let search = dfs('a', |n| graph[n].clone());
+        assert_eq!(vec!['a', 'b', 'd', 'f', 'c'], search.order());
+        assert_eq!(Some(4), search.distance(&'c'));
+        assert_eq!(Some(&'f'), search.parent(&'c'));
+    }
+
+    #[test]
+    fn weighted_searches_find_cheapest_path() {
+        let graph = graph();
+        let search = dijkstra('a', None, |n| graph[n].clone());
+        assert_eq!(Some(3), search.distance(&'f'));
+        assert_eq!(Some(4), search.distance(&'c'));
+        assert_eq!(Some(vec!['a', 'b', 'd', 'f', 'c']), search.path_to(&'c'));
+
+        let search = astar('a', &'c', |n| graph[n].clone(), |_n| 0);
+        assert_eq!(Some(4), search.distance(&'c'));
+        assert_eq!(Some(&'c'), search.order().last());
+
+        // The same graph with nodes numbered from 'a' = 0
+        let index = |c: char| c as usize - 'a' as usize;
+        let paths = dense_dijkstra(6, 0, None, |n| {
+            graph.get(&((b'a' + n as u8) as char))
+                 .into_iter()
+                 .flatten()
+                 .map(|&(m, cost)| (index(m), cost))
+                 .collect::<Vec<_>>()
+        });
+        assert_eq!(Some(4), paths.distance(index('c')));
+        assert_eq!(Some(vec![0, 1, 3, 5, 2]), paths.path_to(index('c')));
+        assert_eq!(None, paths.distance(index('e')));
+    }
+}
@@ -0,0 +1,103 @@
+use super::grid::{Pos, UnboundedGrid, extend_bounds};
+use std::collections::HashMap;
+use std::ops::{Index,IndexMut};
+
+/* An infinite 2-dimensional grid like InfiniteGrid, but storing only the
+ * positions that have been written, so memory use does not depend on how far
+ * they are from the origin. */
+#[derive(Clone)]
+pub struct SparseGrid<T: Clone+PartialEq> {
+    cells: HashMap<Pos, T>,
+    default: T,
+    bounds: Option<(Pos, Pos)>,
+}
+
+impl<T: Clone+PartialEq> SparseGrid<T> {
+    pub fn new(default: T) -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    // Every written position with its value, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item=(Pos, &T)> {
+        self.cells.iter().map(|(&pos, v)| (pos, v))
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item=&mut T> {
+        self.cells.values_mut()
+    }
+}
+
+impl<T: Clone+PartialEq> UnboundedGrid<T> for SparseGrid<T> {
+    fn with_default(default: T) -> Self {
+        Self::new(default)
+    }
+
+    fn bounds(&self) -> Option<(Pos, Pos)> {
+        self.bounds
+    }
+
+    fn iter_non_default<'a>(&'a self) -> impl Iterator<Item=(Pos, &'a T)>
+        where T: 'a
+    {
+        self.iter().filter(move |&(_pos, v)| *v != self.default)
+    }
+}
+
+impl<T: Clone+PartialEq> Index<Pos> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.cells.get(&pos).unwrap_or(&self.default)
+    }
+}
+
+impl<T: Clone+PartialEq> IndexMut<Pos> for SparseGrid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.bounds = extend_bounds(self.bounds, pos);
+        self.cells.entry(pos).or_insert_with(|| self.default.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparseGrid;
+    use super::super::grid::{InfiniteGrid, Pos, UnboundedGrid};
+    use crate::pos;
+
+    fn draw<G: UnboundedGrid<char>>() -> G {
+        let mut grid = G::with_default('.');
+        grid[pos!(-1, 2)] = 'a';
+        grid[pos!(1, 0)] = 'b';
+        grid[pos!(0, 1)] = '.';
+        grid
+    }
+
+    #[test]
+    fn far_writes_stay_small() {
+        let mut grid: SparseGrid<u8> = SparseGrid::new(0);
+        grid[pos!(1_000_000, -1_000_000)] = 7;
+        assert_eq!(7, grid[pos!(1_000_000, -1_000_000)]);
+        assert_eq!(0, grid[pos!(0, 0)]);
+        assert_eq!(1, grid.iter().count());
+    }
+
+    #[test]
+    fn matches_infinite_grid() {
+        let sparse: SparseGrid<char> = draw();
+        let dense: InfiniteGrid<char> = draw();
+        assert_eq!(dense.bounds(), sparse.bounds());
+        assert_eq!(dense.crop(), sparse.crop());
+        assert_eq!(vec!["..a".to_string(), "...".to_string(), "b..".to_string()],
+                   sparse.crop()
+                         .iter()
+                         .map(|row| row.iter().collect::<String>())
+                         .collect::<Vec<String>>());
+        assert_eq!(2, sparse.iter_non_default().count());
+        assert_eq!(dense.count_where(|&c| c != 'b'),
+                   sparse.count_where(|&c| c != 'b'));
+    }
+}